@@ -1,7 +1,5 @@
 #![no_std]
 #![no_main]
-#![allow(incomplete_features)]
-#![feature(generic_const_exprs)]
 use adafruit_seesaw::{devices::ArcadeButton1x4, prelude::*, SeesawSingleThread};
 use cortex_m_rt::entry;
 use rtt_target::{rprintln, rtt_init_print};
@@ -0,0 +1,23 @@
+//! Run on a Linux host, e.g. a Raspberry Pi, with a NeoKey1x4 on `/dev/i2c-1`:
+//!
+//! ```sh
+//! cargo run --example linux_neokey_1x4 --features linux --target aarch64-unknown-linux-gnu
+//! ```
+use adafruit_seesaw::{devices::NeoKey1x4, prelude::*, SeesawSingleThread};
+
+const RED: (u8, u8, u8) = (255, 0, 0);
+const GREEN: (u8, u8, u8) = (0, 255, 0);
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let seesaw = SeesawSingleThread::new_linux("/dev/i2c-1")?;
+    let mut neokeys = NeoKey1x4::new_with_default_addr(seesaw.acquire_driver()).init()?;
+
+    loop {
+        let keys = neokeys.keys()?;
+        let color = |i: u8| if (keys >> i) & 1 == 0 { GREEN } else { RED };
+
+        neokeys.set_neopixel_colors(&[color(0), color(1), color(2), color(3)])?;
+        neokeys.sync_neopixel()?;
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+}
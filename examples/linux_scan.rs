@@ -0,0 +1,32 @@
+//! List the seesaw devices on a Linux I2C bus:
+//!
+//! ```sh
+//! cargo run --example linux_scan --features linux --target aarch64-unknown-linux-gnu -- /dev/i2c-1
+//! ```
+use adafruit_seesaw::{bus, SeesawSingleThread};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "/dev/i2c-1".into());
+    let seesaw = SeesawSingleThread::new_linux(&path)?;
+
+    let found = bus::scan::<16, _>(&mut seesaw.acquire_driver());
+    for dev in &found {
+        println!(
+            "{:#04x}: {:?}, product {} ({}-{:02}-{:02}), {}",
+            dev.addr,
+            dev.hardware_id,
+            dev.product_id,
+            dev.date_code.year,
+            dev.date_code.month,
+            dev.date_code.day,
+            dev.device
+                .map_or("unknown device".into(), |device| format!("{device:?}")),
+        );
+    }
+    if found.is_empty() {
+        println!("No seesaw devices found on {path}");
+    }
+    Ok(())
+}
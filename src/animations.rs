@@ -0,0 +1,337 @@
+//! NeoPixel effects, rendered a frame at a time without allocating.
+//!
+//! An [`Animator`] holds the frame for `N` pixels and the [`Effect`] playing on
+//! them. Each [`Animator::tick`] renders the effect as of `now`, in the units
+//! of whatever clock the application uses, and only writes the frame out to
+//! the device and shows it when it changed. The device's NeoPixel brightness
+//! scales the frame as it's written, so effects always render at full
+//! brightness.
+//!
+//! ```ignore
+//! let mut animator = Animator::<4>::new(Effect::Rainbow { period: 2_000 });
+//! loop {
+//!     animator.tick(&mut neokeys, timer.now_ms())?;
+//!     if let Ok(keys) = neokeys.keys() {
+//!         if keys != 0 {
+//!             animator.set_effect(Effect::FadeTo {
+//!                 color: Rgb::BLACK,
+//!                 duration: 500,
+//!             });
+//!         }
+//!     }
+//! }
+//! ```
+use crate::{driver::Driver, modules::neopixel::NeopixelModule, Rgb, SeesawDevice, SeesawError};
+use core::marker::PhantomData;
+
+/// Something to play on the pixels. Durations are in the clock's units, and
+/// start from the first tick after the effect is set.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Effect {
+    /// Every pixel holds `color`
+    Solid { color: Rgb },
+    /// The color wheel spread across the pixels, going round once per
+    /// `period`
+    Rainbow { period: u64 },
+    /// `color` fading in and out once per `period`
+    Breathe { color: Rgb, period: u64 },
+    /// Every `spacing`th pixel lit with `color`, moving on a pixel every
+    /// `step`
+    Chase {
+        color: Rgb,
+        background: Rgb,
+        spacing: usize,
+        step: u64,
+    },
+    /// `color` for `on`, then off for `off`
+    Blink { color: Rgb, on: u64, off: u64 },
+    /// Fade from what was on the pixels to `color` over `duration`
+    FadeTo { color: Rgb, duration: u64 },
+    /// One random pixel at a time flashing `color` for `every`, over
+    /// `background`
+    Sparkle {
+        color: Rgb,
+        background: Rgb,
+        every: u64,
+    },
+}
+
+/// Plays an [`Effect`] on `N` pixels.
+///
+/// `N` has to be the `N_LEDS` of the device it ticks, which is checked when
+/// the program is built:
+///
+/// ```compile_fail
+/// # use adafruit_seesaw::{animations::{Animator, Effect}, sim::SimDevice, NeoKey1x4, Rgb, SeesawDevice};
+/// let mut neokeys = NeoKey1x4::new(0x30, SimDevice::new(0x30, NeoKey1x4::hardware_id()));
+/// // The NeoKey 1x4 has 4 pixels
+/// let mut animator = Animator::<3>::new(Effect::Solid { color: Rgb::RED });
+/// animator.tick(&mut neokeys, 0);
+/// ```
+#[derive(Debug)]
+pub struct Animator<const N: usize> {
+    effect: Effect,
+    started_at: Option<u64>,
+    /// The frame an effect started from, for fades
+    from: [Rgb; N],
+    frame: [Rgb; N],
+    shown: bool,
+    rng: u32,
+    /// The sparkle period and the pixel lit in it
+    sparkle: Option<(u64, usize)>,
+}
+
+impl<const N: usize> Animator<N> {
+    pub fn new(effect: Effect) -> Self {
+        Self {
+            effect,
+            started_at: None,
+            from: [Rgb::BLACK; N],
+            frame: [Rgb::BLACK; N],
+            shown: false,
+            rng: 0x2545_F491,
+            sparkle: None,
+        }
+    }
+
+    pub fn effect(&self) -> Effect {
+        self.effect
+    }
+
+    /// Switch to `effect`, which starts from the current frame on the next
+    /// tick
+    pub fn set_effect(&mut self, effect: Effect) {
+        self.effect = effect;
+        self.started_at = None;
+        self.from = self.frame;
+        self.sparkle = None;
+    }
+
+    /// The last frame rendered
+    pub fn frame(&self) -> &[Rgb; N] {
+        &self.frame
+    }
+
+    /// Render the frame for `now`, and write it to the device and show it if
+    /// it changed. Returns whether it did.
+    pub fn tick<D, DEV>(
+        &mut self,
+        device: &mut DEV,
+        now: u64,
+    ) -> Result<bool, SeesawError<D::I2cError>>
+    where
+        D: Driver,
+        DEV: SeesawDevice<Driver = D> + NeopixelModule<D>,
+    {
+        let () = Length::<D, DEV, N>::MATCHES;
+        if !self.render(now) && self.shown {
+            return Ok(false);
+        }
+        device.set_neopixel_rgbs(&self.frame)?;
+        device.sync_neopixel()?;
+        self.shown = true;
+        Ok(true)
+    }
+
+    /// Render the frame for `now`, returning whether it changed
+    pub fn render(&mut self, now: u64) -> bool {
+        let started_at = *self.started_at.get_or_insert(now);
+        let t = now.saturating_sub(started_at);
+        let mut frame = self.frame;
+
+        match self.effect {
+            Effect::Solid { color } => frame = [color; N],
+            Effect::Rainbow { period } => {
+                let turn = t % period.max(1) * 256 / period.max(1);
+                for (i, pixel) in frame.iter_mut().enumerate() {
+                    *pixel = wheel((turn + (i * 256 / N) as u64) as u8);
+                }
+            }
+            Effect::Breathe { color, period } => {
+                let period = period.max(2);
+                let phase = t % period;
+                let half = period / 2;
+                let level = if phase < half {
+                    phase * 255 / half
+                } else {
+                    (period - phase) * 255 / (period - half)
+                };
+                frame = [color.scale(level.min(255) as u8); N];
+            }
+            Effect::Chase {
+                color,
+                background,
+                spacing,
+                step,
+            } => {
+                let spacing = spacing.max(1);
+                let offset = (t / step.max(1) % spacing as u64) as usize;
+                for (i, pixel) in frame.iter_mut().enumerate() {
+                    *pixel = if i % spacing == offset {
+                        color
+                    } else {
+                        background
+                    };
+                }
+            }
+            Effect::Blink { color, on, off } => {
+                let lit = t % (on + off).max(1) < on;
+                frame = [if lit { color } else { Rgb::BLACK }; N];
+            }
+            Effect::FadeTo { color, duration } => {
+                let level = (t.min(duration) * 255 / duration.max(1)) as u8;
+                for (pixel, &from) in frame.iter_mut().zip(self.from.iter()) {
+                    *pixel = mix(from, color, level);
+                }
+            }
+            Effect::Sparkle {
+                color,
+                background,
+                every,
+            } => {
+                let period = t / every.max(1);
+                let lit = match self.sparkle {
+                    Some((last, lit)) if last == period => lit,
+                    _ => self.next_random() as usize % N.max(1),
+                };
+                self.sparkle = Some((period, lit));
+                for (i, pixel) in frame.iter_mut().enumerate() {
+                    *pixel = if i == lit { color } else { background };
+                }
+            }
+        }
+
+        let changed = frame != self.frame;
+        self.frame = frame;
+        changed
+    }
+
+    /// xorshift32
+    fn next_random(&mut self) -> u32 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 17;
+        self.rng ^= self.rng << 5;
+        self.rng
+    }
+}
+
+/// Checks that an animator is as long as a device's strip
+struct Length<D, DEV, const N: usize>(PhantomData<(D, DEV)>);
+
+impl<D: Driver, DEV: NeopixelModule<D>, const N: usize> Length<D, DEV, N> {
+    const MATCHES: () = assert!(
+        N == DEV::N_LEDS as usize,
+        "the animator isn't as long as the device's N_LEDS"
+    );
+}
+
+/// The color at `position` round a red, blue and green color wheel
+pub fn wheel(position: u8) -> Rgb {
+    match position {
+        0..=84 => Rgb::new(255 - position * 3, 0, position * 3),
+        85..=169 => Rgb::new(0, (position - 85) * 3, 255 - (position - 85) * 3),
+        _ => Rgb::new((position - 170) * 3, 255 - (position - 170) * 3, 0),
+    }
+}
+
+/// `level` out of 255 of the way from `from` to `to`
+fn mix(from: Rgb, to: Rgb, level: u8) -> Rgb {
+    let mix = |a: u8, b: u8| (a as i32 + (b as i32 - a as i32) * level as i32 / 255) as u8;
+    Rgb::new(mix(from.r, to.r), mix(from.g, to.g), mix(from.b, to.b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{wheel, Animator, Effect};
+    use crate::{
+        devices::NeoKey1x4,
+        mock::{write, MockDriver},
+        Rgb, SeesawDevice,
+    };
+
+    #[test]
+    fn only_changed_frames_are_written() {
+        let frame = [&[0, 0][..], &[255, 0, 0].repeat(4)].concat();
+        let mut dev = NeoKey1x4::new(
+            0x30,
+            MockDriver::new(&[
+                write(0x30, [0x0E, 0x04], &frame),
+                write(0x30, [0x0E, 0x05], &[]),
+            ]),
+        );
+        let mut animator = Animator::<4>::new(Effect::Solid { color: Rgb::RED });
+        assert!(animator.tick(&mut dev, 0).unwrap());
+        assert!(!animator.tick(&mut dev, 10).unwrap());
+        dev.driver().done();
+    }
+
+    #[test]
+    fn fade_starts_from_the_current_frame() {
+        let mut animator = Animator::<2>::new(Effect::Solid { color: Rgb::RED });
+        animator.render(0);
+        animator.set_effect(Effect::FadeTo {
+            color: Rgb::BLUE,
+            duration: 100,
+        });
+        animator.render(50);
+        assert_eq!(animator.frame(), &[Rgb::RED; 2]);
+        animator.render(100);
+        assert_eq!(animator.frame(), &[Rgb::new(128, 0, 127); 2]);
+        animator.render(150);
+        assert_eq!(animator.frame(), &[Rgb::BLUE; 2]);
+    }
+
+    #[test]
+    fn chase_and_blink() {
+        let mut animator = Animator::<4>::new(Effect::Chase {
+            color: Rgb::RED,
+            background: Rgb::BLACK,
+            spacing: 2,
+            step: 10,
+        });
+        animator.render(0);
+        assert_eq!(
+            animator.frame(),
+            &[Rgb::RED, Rgb::BLACK, Rgb::RED, Rgb::BLACK]
+        );
+        animator.render(10);
+        assert_eq!(
+            animator.frame(),
+            &[Rgb::BLACK, Rgb::RED, Rgb::BLACK, Rgb::RED]
+        );
+
+        animator.set_effect(Effect::Blink {
+            color: Rgb::RED,
+            on: 5,
+            off: 5,
+        });
+        animator.render(100);
+        assert_eq!(animator.frame(), &[Rgb::RED; 4]);
+        assert!(animator.render(105));
+        assert_eq!(animator.frame(), &[Rgb::BLACK; 4]);
+    }
+
+    #[test]
+    fn rainbow_spreads_the_wheel() {
+        let mut animator = Animator::<4>::new(Effect::Rainbow { period: 1_000 });
+        animator.render(0);
+        assert_eq!(animator.frame(), &[0, 64, 128, 192].map(wheel));
+    }
+
+    #[test]
+    fn sparkle_lights_one_pixel_per_period() {
+        let mut animator = Animator::<8>::new(Effect::Sparkle {
+            color: Rgb::RED,
+            background: Rgb::BLACK,
+            every: 10,
+        });
+        animator.render(0);
+        let lit =
+            |animator: &Animator<8>| animator.frame().iter().filter(|&&p| p == Rgb::RED).count();
+        assert_eq!(lit(&animator), 1);
+        assert!(!animator.render(9));
+        animator.render(10);
+        assert_eq!(lit(&animator), 1);
+    }
+}
@@ -0,0 +1,49 @@
+//! Probe and poke seesaw devices on a Linux I2C bus. Run with `help` for the
+//! list of commands.
+use adafruit_seesaw::{
+    cli::{self, CliError},
+    SeesawSingleThread,
+};
+use std::process::ExitCode;
+
+const DEFAULT_BUS: &str = "/dev/i2c-1";
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let path = match args.iter().position(|arg| arg == "--bus") {
+        Some(i) if i + 1 < args.len() => {
+            let path = args.remove(i + 1);
+            args.remove(i);
+            path
+        }
+        Some(_) => {
+            eprintln!("{}", cli::USAGE);
+            return ExitCode::FAILURE;
+        }
+        None => DEFAULT_BUS.into(),
+    };
+
+    let seesaw = match SeesawSingleThread::new_linux(&path) {
+        Ok(seesaw) => seesaw,
+        Err(err) => {
+            eprintln!("Failed to open {path}: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match cli::run(
+        seesaw.acquire_driver(),
+        &mut std::io::stdout().lock(),
+        &args,
+    ) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(CliError::Usage) => {
+            eprintln!("{}", cli::USAGE);
+            ExitCode::FAILURE
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,4 +1,12 @@
-use crate::driver::I2cDriver;
+use crate::{
+    devices::KnownDevice,
+    driver::{Driver, I2cDriver},
+    modules::status::{
+        DeviceCapabilities, ProductDateCode, STATUS_HW_ID, STATUS_OPTIONS, STATUS_VERSION,
+    },
+    DriverExt, HardwareId,
+};
+use core::ops::RangeInclusive;
 use embedded_hal::blocking::{delay, i2c};
 use shared_bus::BusMutex;
 
@@ -10,6 +18,25 @@ pub struct BusProxy<'a, M> {
 #[derive(Debug)]
 pub struct Bus<DELAY, I2C>(pub(crate) DELAY, pub(crate) I2C);
 
+/// A [`BusMutex`] that locks the bus inside a `critical-section` critical
+/// section
+#[cfg(feature = "critical-section")]
+#[derive(Debug)]
+pub struct CriticalSectionMutex<BUS>(critical_section::Mutex<core::cell::RefCell<BUS>>);
+
+#[cfg(feature = "critical-section")]
+impl<BUS> BusMutex for CriticalSectionMutex<BUS> {
+    type Bus = BUS;
+
+    fn create(v: BUS) -> Self {
+        Self(critical_section::Mutex::new(core::cell::RefCell::new(v)))
+    }
+
+    fn lock<R, F: FnOnce(&mut BUS) -> R>(&self, f: F) -> R {
+        critical_section::with(|cs| f(&mut self.0.borrow_ref_mut(cs)))
+    }
+}
+
 // Clone implementation
 impl<'a, DELAY, I2C, M> Clone for BusProxy<'a, M>
 where
@@ -84,3 +111,178 @@ where
             .map_err(|err| err.into())
     }
 }
+
+impl<DELAY, I2C> Bus<DELAY, I2C> {
+    /// Pair a delay with an I2C bus. For async drivers, the resulting `Bus`
+    /// can be handed to a device directly.
+    pub fn new(delay: DELAY, i2c: I2C) -> Self {
+        Bus(delay, i2c)
+    }
+}
+
+/// The addresses probed by [`scan`]: every 7-bit address except the reserved
+/// ones at either end
+pub const SCAN_ADDRESSES: RangeInclusive<u8> = 0x08..=0x77;
+
+/// A seesaw device found by [`scan`]
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DiscoveredDevice {
+    pub addr: u8,
+    pub hardware_id: HardwareId,
+    pub product_id: u16,
+    pub date_code: ProductDateCode,
+    pub capabilities: DeviceCapabilities,
+    /// The matching device type in [`devices`](crate::devices), if the
+    /// product ID is a known one
+    pub device: Option<KnownDevice>,
+}
+
+/// Fixed-capacity list of the devices found by [`scan`]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DiscoveredDevices<const N: usize> {
+    devices: [Option<DiscoveredDevice>; N],
+    len: usize,
+    missed: usize,
+}
+
+impl<const N: usize> DiscoveredDevices<N> {
+    fn push(&mut self, device: DiscoveredDevice) {
+        match self.devices.get_mut(self.len) {
+            Some(slot) => {
+                *slot = Some(device);
+                self.len += 1;
+            }
+            None => self.missed += 1,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of devices that were found but didn't fit in the list
+    pub fn missed(&self) -> usize {
+        self.missed
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &DiscoveredDevice> {
+        self.devices[..self.len].iter().flatten()
+    }
+}
+
+impl<const N: usize> Default for DiscoveredDevices<N> {
+    fn default() -> Self {
+        Self {
+            devices: [None; N],
+            len: 0,
+            missed: 0,
+        }
+    }
+}
+
+impl<'a, const N: usize> IntoIterator for &'a DiscoveredDevices<N> {
+    type IntoIter = core::iter::Flatten<core::slice::Iter<'a, Option<DiscoveredDevice>>>;
+    type Item = &'a DiscoveredDevice;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.devices[..self.len].iter().flatten()
+    }
+}
+
+/// Probe every address in [`SCAN_ADDRESSES`] for a seesaw device, returning
+/// up to `N` of them.
+///
+/// An address holds a seesaw if it answers a read of the `STATUS_HW_ID`
+/// register with a known [`HardwareId`]. Note that probing writes a register
+/// address to every device on the bus, so non-seesaw devices may see a
+/// spurious write.
+///
+/// ```ignore
+/// let found = bus::scan::<8, _>(&mut seesaw.acquire_driver());
+/// ```
+pub fn scan<const N: usize, D: Driver>(driver: &mut D) -> DiscoveredDevices<N> {
+    scan_range(driver, SCAN_ADDRESSES)
+}
+
+/// Like [`scan`], but only probe the addresses in `addrs`
+pub fn scan_range<const N: usize, D: Driver>(
+    driver: &mut D,
+    addrs: RangeInclusive<u8>,
+) -> DiscoveredDevices<N> {
+    let mut found = DiscoveredDevices::default();
+    addrs
+        .filter_map(|addr| probe(driver, addr))
+        .for_each(|device| found.push(device));
+    found
+}
+
+/// Identify the seesaw device at `addr`, if there is one
+pub fn probe<D: Driver>(driver: &mut D, addr: u8) -> Option<DiscoveredDevice> {
+    let hardware_id = driver
+        .read_u8(addr, STATUS_HW_ID)
+        .ok()
+        .and_then(|id| HardwareId::try_from(id).ok())?;
+    let date_code = driver
+        .read_u32(addr, STATUS_VERSION)
+        .map(ProductDateCode::from)
+        .ok()?;
+    let capabilities = driver
+        .read_u32(addr, STATUS_OPTIONS)
+        .map(DeviceCapabilities::from)
+        .ok()?;
+
+    Some(DiscoveredDevice {
+        addr,
+        hardware_id,
+        product_id: date_code.id,
+        date_code,
+        capabilities,
+        device: KnownDevice::from_product_id(date_code.id)
+            .filter(|device| device.hardware_id() == hardware_id),
+    })
+}
+
+#[cfg(feature = "async")]
+mod asynch {
+    use super::Bus;
+    use embedded_hal_async::{
+        delay::DelayNs,
+        i2c::{ErrorType, I2c, Operation, SevenBitAddress},
+    };
+
+    // Delay implementation
+    impl<DELAY: DelayNs, I2C> DelayNs for Bus<DELAY, I2C> {
+        async fn delay_ns(&mut self, ns: u32) {
+            self.0.delay_ns(ns).await
+        }
+
+        async fn delay_us(&mut self, us: u32) {
+            self.0.delay_us(us).await
+        }
+
+        async fn delay_ms(&mut self, ms: u32) {
+            self.0.delay_ms(ms).await
+        }
+    }
+
+    // I2C implementations
+    impl<DELAY, I2C: ErrorType> ErrorType for Bus<DELAY, I2C> {
+        type Error = I2C::Error;
+    }
+
+    impl<DELAY, I2C: I2c> I2c for Bus<DELAY, I2C> {
+        async fn transaction(
+            &mut self,
+            addr: SevenBitAddress,
+            operations: &mut [Operation<'_>],
+        ) -> Result<(), Self::Error> {
+            self.1.transaction(addr, operations).await
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! The commands behind the `seesaw-cli` binary.
+//!
+//! [`run`] parses a single command line and runs it with any [`Driver`], so
+//! the tool works the same on a Linux I2C bus as on a
+//! [`sim`](crate::sim) bus in tests.
+use crate::{
+    bus::{self, DiscoveredDevice},
+    driver::{Driver, DriverExt, MAX_WRITE_LEN},
+    modules::{
+        gpio::{self, PinMode},
+        status::DeviceCapabilities,
+    },
+    DynamicDevice, SeesawError,
+};
+use std::{fmt, io::Write, string::String, vec::Vec};
+
+pub const USAGE: &str = "\
+usage: seesaw-cli [--bus <path>] <command>
+
+commands:
+  scan
+  info <addr>
+  gpio read <addr> <pin>
+  gpio write <addr> <pin> <high|low>
+  gpio mode <addr> <pin> <input|output|pullup|pulldown>
+  adc read <addr> <pin>
+  pixel set <addr> <pin> <leds> <index> <rrggbb>
+  encoder watch <addr> [count]
+  reg read <addr> <module> <function> <1|2|4>
+  reg write <addr> <module> <function> <byte>...
+
+Numbers may be decimal or 0x-prefixed hex.";
+
+/// How long `encoder watch` waits between polls, in microseconds
+const WATCH_INTERVAL: u32 = 10_000;
+
+#[derive(Debug)]
+pub enum CliError<E> {
+    /// The command line didn't match a command
+    Usage,
+    /// An argument couldn't be parsed or is out of range
+    InvalidArgument(String),
+    /// No seesaw device answered at this address
+    NoDevice(u8),
+    Seesaw(SeesawError<E>),
+    Io(std::io::Error),
+}
+
+impl<E> From<SeesawError<E>> for CliError<E> {
+    fn from(err: SeesawError<E>) -> Self {
+        CliError::Seesaw(err)
+    }
+}
+
+impl<E> From<std::io::Error> for CliError<E> {
+    fn from(err: std::io::Error) -> Self {
+        CliError::Io(err)
+    }
+}
+
+impl<E> From<InvalidArgument<'_>> for CliError<E> {
+    fn from(InvalidArgument(arg): InvalidArgument) -> Self {
+        CliError::InvalidArgument(arg.into())
+    }
+}
+
+impl<E: fmt::Debug> fmt::Display for CliError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage => f.write_str(USAGE),
+            CliError::InvalidArgument(arg) => write!(f, "invalid argument `{arg}`"),
+            CliError::NoDevice(addr) => write!(f, "no seesaw device at {addr:#04x}"),
+            CliError::Seesaw(err) => write!(f, "{err}"),
+            CliError::Io(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> std::error::Error for CliError<E> {}
+
+/// An argument that couldn't be parsed
+struct InvalidArgument<'a>(&'a str);
+
+/// Run the command in `args` (not including the program name) on the bus
+/// behind `driver`, writing its output to `out`
+pub fn run<D, W, S>(mut driver: D, out: &mut W, args: &[S]) -> Result<(), CliError<D::I2cError>>
+where
+    D: Driver,
+    W: Write,
+    S: AsRef<str>,
+{
+    let args: Vec<&str> = args.iter().map(AsRef::as_ref).collect();
+
+    match args[..] {
+        ["help"] => writeln!(out, "{USAGE}")?,
+        ["scan"] => {
+            let found = bus::scan::<32, _>(&mut driver);
+            for device in &found {
+                writeln!(out, "{}", Summary(device))?;
+            }
+            if found.is_empty() {
+                writeln!(out, "no devices found")?;
+            } else if found.missed() > 0 {
+                writeln!(out, "and {} more", found.missed())?;
+            }
+        }
+        ["info", addr] => {
+            let found = probe(&mut driver, addr)?;
+            let mut dev = DynamicDevice::from_discovered(&found, driver);
+            writeln!(out, "address:      {:#04x}", found.addr)?;
+            writeln!(
+                out,
+                "hardware id:  {:?} ({:#04x})",
+                found.hardware_id,
+                u8::from(found.hardware_id)
+            )?;
+            match found.device {
+                Some(device) => writeln!(out, "product:      {} ({device:?})", found.product_id)?,
+                None => writeln!(out, "product:      {}", found.product_id)?,
+            }
+            writeln!(
+                out,
+                "date code:    {}-{:02}-{:02}",
+                found.date_code.year, found.date_code.month, found.date_code.day
+            )?;
+            writeln!(out, "capabilities: {}", Capabilities(&found.capabilities))?;
+            writeln!(out, "temperature:  {:.2} C", dev.temp()?)?;
+        }
+        ["gpio", "read", addr, pin] => {
+            let pin = number(pin)?;
+            let mut dev = device(driver, addr)?;
+            let mask = gpio::pin_mask(dev.hardware_id(), pin)?;
+            let levels = dev.digital_read_bulk()?;
+            writeln!(out, "{}", level(levels & mask != 0))?;
+        }
+        ["gpio", "write", addr, pin, value] => {
+            let pin = number(pin)?;
+            let value = match value {
+                "high" | "1" => true,
+                "low" | "0" => false,
+                _ => return Err(InvalidArgument(value).into()),
+            };
+            device(driver, addr)?.digital_write(pin, value)?;
+        }
+        ["gpio", "mode", addr, pin, mode] => {
+            let pin = number(pin)?;
+            let mode = match mode {
+                "input" => PinMode::Input,
+                "output" => PinMode::Output,
+                "pullup" => PinMode::InputPullup,
+                "pulldown" => PinMode::InputPulldown,
+                _ => return Err(InvalidArgument(mode).into()),
+            };
+            device(driver, addr)?.set_pin_mode(pin, mode)?;
+        }
+        ["adc", "read", addr, pin] => {
+            let pin = number(pin)?;
+            writeln!(out, "{}", device(driver, addr)?.analog_read(pin)?)?;
+        }
+        ["pixel", "set", addr, pin, leds, index_arg, color] => {
+            let pin = number(pin)?;
+            let leds = number(leds)?;
+            let index = number(index_arg)?;
+            if index >= leds {
+                return Err(InvalidArgument(index_arg).into());
+            }
+            let (r, g, b) = rgb(color)?;
+            let mut dev = device(driver, addr)?;
+            dev.enable_neopixel(pin, leds)?;
+            dev.set_nth_neopixel_color(index, r, g, b)?;
+            dev.sync_neopixel()?;
+        }
+        ["encoder", "watch", addr] => watch(device(driver, addr)?, out, None)?,
+        ["encoder", "watch", addr, count] => {
+            let count = number(count)?;
+            watch(device(driver, addr)?, out, Some(count))?;
+        }
+        ["reg", "read", addr, module, function, width] => {
+            let addr = number(addr)?;
+            let reg = [number(module)?, number(function)?];
+            let (value, digits) = match width {
+                "1" => (driver.read_u8(addr, &reg).map(u32::from), 2),
+                "2" => (driver.read_u16(addr, &reg).map(u32::from), 4),
+                "4" => (driver.read_u32(addr, &reg), 8),
+                _ => return Err(InvalidArgument(width).into()),
+            };
+            let value = value.map_err(SeesawError::i2c(&reg))?;
+            writeln!(out, "{value:#0w$x}", w = 2 + digits)?;
+        }
+        ["reg", "write", addr, module, function, ref bytes @ ..] if !bytes.is_empty() => {
+            let addr = number(addr)?;
+            let reg = [number(module)?, number(function)?];
+            if bytes.len() > MAX_WRITE_LEN {
+                return Err(InvalidArgument(bytes[MAX_WRITE_LEN]).into());
+            }
+            let bytes = bytes
+                .iter()
+                .map(|byte| number(byte))
+                .collect::<Result<Vec<u8>, _>>()?;
+            driver.register_write(addr, &reg, &bytes)?;
+        }
+        _ => return Err(CliError::Usage),
+    }
+    Ok(())
+}
+
+/// Print the encoder's position every time it moves, until it has moved
+/// `count` times
+fn watch<D: Driver, W: Write>(
+    mut dev: DynamicDevice<D>,
+    out: &mut W,
+    count: Option<u32>,
+) -> Result<(), CliError<D::I2cError>> {
+    let mut moves = 0;
+    while count != Some(moves) {
+        match dev.delta()? {
+            0 => dev.driver().delay_us(WATCH_INTERVAL),
+            delta => {
+                writeln!(out, "{} ({delta:+})", dev.position()?)?;
+                moves += 1;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn probe<D: Driver>(driver: &mut D, addr: &str) -> Result<DiscoveredDevice, CliError<D::I2cError>> {
+    let addr = number(addr)?;
+    bus::probe(driver, addr).ok_or(CliError::NoDevice(addr))
+}
+
+fn device<D: Driver>(mut driver: D, addr: &str) -> Result<DynamicDevice<D>, CliError<D::I2cError>> {
+    probe(&mut driver, addr).map(|found| DynamicDevice::from_discovered(&found, driver))
+}
+
+/// Parse a decimal or `0x`-prefixed hex number
+fn number<T: TryFrom<u32>>(arg: &str) -> Result<T, InvalidArgument<'_>> {
+    match arg.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => arg.parse(),
+    }
+    .ok()
+    .and_then(|n| T::try_from(n).ok())
+    .ok_or(InvalidArgument(arg))
+}
+
+/// Parse an `rrggbb` hex color, with an optional leading `#`
+fn rgb(arg: &str) -> Result<(u8, u8, u8), InvalidArgument<'_>> {
+    let hex = arg.strip_prefix('#').unwrap_or(arg);
+    match (hex.len(), u32::from_str_radix(hex, 16)) {
+        (6, Ok(color)) => {
+            let [_, r, g, b] = color.to_be_bytes();
+            Ok((r, g, b))
+        }
+        _ => Err(InvalidArgument(arg)),
+    }
+}
+
+fn level(high: bool) -> &'static str {
+    if high {
+        "high"
+    } else {
+        "low"
+    }
+}
+
+/// One line describing a device found by a scan
+struct Summary<'a>(&'a DiscoveredDevice);
+
+impl fmt::Display for Summary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dev = self.0;
+        write!(
+            f,
+            "{:#04x}  {:?}  product {}",
+            dev.addr, dev.hardware_id, dev.product_id
+        )?;
+        match dev.device {
+            Some(device) => write!(f, " ({device:?})"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The names of the modules a device has
+struct Capabilities<'a>(&'a DeviceCapabilities);
+
+impl fmt::Display for Capabilities<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let caps = self.0;
+        let names = [
+            (caps.status, "status"),
+            (caps.gpio, "gpio"),
+            (caps.sercom0, "sercom0"),
+            (caps.timer, "timer"),
+            (caps.adc, "adc"),
+            (caps.dac, "dac"),
+            (caps.interrupt, "interrupt"),
+            (caps.dap, "dap"),
+            (caps.eeprom, "eeprom"),
+            (caps.neopixel, "neopixel"),
+            (caps.touch, "touch"),
+            (caps.keypad, "keypad"),
+            (caps.encoder, "encoder"),
+            (caps.spectrum, "spectrum"),
+        ];
+        let mut first = true;
+        for (_, name) in names.iter().filter(|(has, _)| *has) {
+            if !first {
+                f.write_str(" ")?;
+            }
+            f.write_str(name)?;
+            first = false;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,271 @@
+/// A color for an RGB NeoPixel.
+///
+/// Converts to and from the `(r, g, b)` tuples the NeoPixel setters take, and
+/// to and from [`Hsv`]:
+///
+/// ```
+/// # use adafruit_seesaw::{Hsv, Rgb};
+/// let orange = Rgb::from(Hsv {
+///     h: 21,
+///     s: 255,
+///     v: 255,
+/// });
+/// assert_eq!(orange, Rgb::new(255, 126, 0));
+/// assert_eq!(<(u8, u8, u8)>::from(orange.gamma()), (255, 54, 0));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const BLACK: Self = Self::new(0, 0, 0);
+    pub const BLUE: Self = Self::new(0, 0, 255);
+    pub const GREEN: Self = Self::new(0, 255, 0);
+    pub const RED: Self = Self::new(255, 0, 0);
+    pub const WHITE: Self = Self::new(255, 255, 255);
+
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Correct for the eye's response to brightness with [`GAMMA_2_2`], so
+    /// that evenly spaced values look evenly spaced on the pixel
+    pub const fn gamma(self) -> Self {
+        Self::new(gamma(self.r), gamma(self.g), gamma(self.b))
+    }
+
+    /// Scale each channel by `level` out of 255
+    pub const fn scale(self, level: u8) -> Self {
+        Self::new(
+            scale(self.r, level),
+            scale(self.g, level),
+            scale(self.b, level),
+        )
+    }
+}
+
+impl From<(u8, u8, u8)> for Rgb {
+    fn from((r, g, b): (u8, u8, u8)) -> Self {
+        Self::new(r, g, b)
+    }
+}
+
+impl From<Rgb> for (u8, u8, u8) {
+    fn from(color: Rgb) -> Self {
+        (color.r, color.g, color.b)
+    }
+}
+
+/// A color for an RGBW NeoPixel, with a separate white channel
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Rgbw {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub w: u8,
+}
+
+impl Rgbw {
+    pub const fn new(r: u8, g: u8, b: u8, w: u8) -> Self {
+        Self { r, g, b, w }
+    }
+
+    /// Correct for the eye's response to brightness with [`GAMMA_2_2`]
+    pub const fn gamma(self) -> Self {
+        Self::new(gamma(self.r), gamma(self.g), gamma(self.b), gamma(self.w))
+    }
+
+    /// Scale each channel by `level` out of 255
+    pub const fn scale(self, level: u8) -> Self {
+        Self::new(
+            scale(self.r, level),
+            scale(self.g, level),
+            scale(self.b, level),
+            scale(self.w, level),
+        )
+    }
+}
+
+/// The color on the RGB LEDs, with the white LED off
+impl From<Rgb> for Rgbw {
+    fn from(Rgb { r, g, b }: Rgb) -> Self {
+        Self::new(r, g, b, 0)
+    }
+}
+
+/// The white LED mixed into the RGB LEDs, for pixels that don't have one
+impl From<Rgbw> for Rgb {
+    fn from(Rgbw { r, g, b, w }: Rgbw) -> Self {
+        Self::new(
+            r.saturating_add(w),
+            g.saturating_add(w),
+            b.saturating_add(w),
+        )
+    }
+}
+
+impl From<(u8, u8, u8)> for Rgbw {
+    fn from(color: (u8, u8, u8)) -> Self {
+        Rgb::from(color).into()
+    }
+}
+
+impl From<(u8, u8, u8, u8)> for Rgbw {
+    fn from((r, g, b, w): (u8, u8, u8, u8)) -> Self {
+        Self::new(r, g, b, w)
+    }
+}
+
+impl From<Rgbw> for (u8, u8, u8, u8) {
+    fn from(color: Rgbw) -> Self {
+        (color.r, color.g, color.b, color.w)
+    }
+}
+
+/// A color as hue, saturation and value, each out of 255. The hue goes round
+/// from red at 0, through green at about 85 and blue at about 170, back to
+/// red.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Hsv {
+    pub h: u8,
+    pub s: u8,
+    pub v: u8,
+}
+
+impl From<Hsv> for Rgb {
+    fn from(Hsv { h, s, v }: Hsv) -> Self {
+        // Which sixth of the wheel `h` is in, and how far along it, out of 255
+        let sixths = h as u32 * 6;
+        let along = sixths & 0xFF;
+        let (s, v) = (s as u32, v as u32);
+        let p = (v * (255 - s) / 255) as u8;
+        let q = (v * (255 - s * along / 255) / 255) as u8;
+        let t = (v * (255 - s * (255 - along) / 255) / 255) as u8;
+        let v = v as u8;
+        match sixths >> 8 {
+            0 => Self::new(v, t, p),
+            1 => Self::new(q, v, p),
+            2 => Self::new(p, v, t),
+            3 => Self::new(p, q, v),
+            4 => Self::new(t, p, v),
+            _ => Self::new(v, p, q),
+        }
+    }
+}
+
+impl From<Rgb> for Hsv {
+    fn from(Rgb { r, g, b }: Rgb) -> Self {
+        let max = r.max(g).max(b);
+        let range = (max - r.min(g).min(b)) as i32;
+        if range == 0 {
+            return Self { h: 0, s: 0, v: max };
+        }
+        // The hue in 256ths of a sixth of the wheel, from the primary that's
+        // brightest
+        let (r, g, b) = (r as i32, g as i32, b as i32);
+        let sixths = if max as i32 == r {
+            256 * (g - b) / range
+        } else if max as i32 == g {
+            512 + 256 * (b - r) / range
+        } else {
+            1024 + 256 * (r - g) / range
+        };
+        Self {
+            h: (sixths.rem_euclid(1536) / 6) as u8,
+            s: (255 * range / max as i32) as u8,
+            v: max,
+        }
+    }
+}
+
+/// Maps a linear brightness to the PWM level that looks that bright, with a
+/// gamma of 2.2
+pub const GAMMA_2_2: [u8; 256] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2,
+    3, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 6, 6, 6, 6, 7, 7, 7, 8, 8, 8, 9, 9, 9, 10, 10, 11, 11,
+    11, 12, 12, 13, 13, 13, 14, 14, 15, 15, 16, 16, 17, 17, 18, 18, 19, 19, 20, 20, 21, 22, 22, 23,
+    23, 24, 25, 25, 26, 26, 27, 28, 28, 29, 30, 30, 31, 32, 33, 33, 34, 35, 35, 36, 37, 38, 39, 39,
+    40, 41, 42, 43, 43, 44, 45, 46, 47, 48, 49, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61,
+    62, 63, 64, 65, 66, 67, 68, 69, 70, 71, 73, 74, 75, 76, 77, 78, 79, 81, 82, 83, 84, 85, 87, 88,
+    89, 90, 91, 93, 94, 95, 97, 98, 99, 100, 102, 103, 105, 106, 107, 109, 110, 111, 113, 114, 116,
+    117, 119, 120, 121, 123, 124, 126, 127, 129, 130, 132, 133, 135, 137, 138, 140, 141, 143, 145,
+    146, 148, 149, 151, 153, 154, 156, 158, 159, 161, 163, 165, 166, 168, 170, 172, 173, 175, 177,
+    179, 181, 182, 184, 186, 188, 190, 192, 194, 196, 197, 199, 201, 203, 205, 207, 209, 211, 213,
+    215, 217, 219, 221, 223, 225, 227, 229, 231, 234, 236, 238, 240, 242, 244, 246, 248, 251, 253,
+    255,
+];
+
+const fn gamma(c: u8) -> u8 {
+    GAMMA_2_2[c as usize]
+}
+
+/// `c` at `level` out of 255, where 255 leaves it as it is
+pub(crate) const fn scale(c: u8, level: u8) -> u8 {
+    ((c as u16 * (level as u16 + 1)) >> 8) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Hsv, Rgb, Rgbw, GAMMA_2_2};
+
+    #[test]
+    fn hsv() {
+        let hue = |h| Rgb::from(Hsv { h, s: 255, v: 255 });
+        assert_eq!(hue(0), Rgb::RED);
+        assert_eq!(hue(43), Rgb::new(253, 255, 0));
+        assert_eq!(hue(128), Rgb::new(0, 255, 255));
+        assert_eq!(Rgb::from(Hsv { h: 99, s: 0, v: 80 }), Rgb::new(80, 80, 80));
+        assert_eq!(
+            Hsv::from(Rgb::GREEN),
+            Hsv {
+                h: 85,
+                s: 255,
+                v: 255
+            }
+        );
+        assert_eq!(
+            Hsv::from(Rgb::BLUE),
+            Hsv {
+                h: 170,
+                s: 255,
+                v: 255
+            }
+        );
+        assert_eq!(
+            Hsv::from(Rgb::new(128, 0, 64)),
+            Hsv {
+                h: 234,
+                s: 255,
+                v: 128
+            }
+        );
+        assert_eq!(Hsv::from(Rgb::new(40, 40, 40)), Hsv { h: 0, s: 0, v: 40 });
+        for h in (0..=255).step_by(5) {
+            let back = Hsv::from(hue(h)).h;
+            assert!(back.abs_diff(h) <= 1, "{h} came back as {back}");
+        }
+    }
+
+    #[test]
+    fn gamma_and_scale() {
+        assert_eq!(GAMMA_2_2[0], 0);
+        assert_eq!(GAMMA_2_2[128], 56);
+        assert_eq!(GAMMA_2_2[255], 255);
+        assert!(GAMMA_2_2.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(Rgb::new(255, 128, 0).gamma(), Rgb::new(255, 56, 0));
+        assert_eq!(Rgb::WHITE.scale(255), Rgb::WHITE);
+        assert_eq!(Rgb::WHITE.scale(127), Rgb::new(127, 127, 127));
+        assert_eq!(Rgb::WHITE.scale(0), Rgb::BLACK);
+        assert_eq!(Rgbw::from(Rgb::RED), Rgbw::new(255, 0, 0, 0));
+        assert_eq!(
+            Rgb::from(Rgbw::new(200, 0, 0, 100)),
+            Rgb::new(255, 100, 100)
+        );
+    }
+}
@@ -1,6 +1,7 @@
 pub type Reg = [u8; 2];
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum HardwareId {
     ATTINY817 = 0x87,
     SAMD09 = 0x55,
@@ -12,8 +13,22 @@ impl From<HardwareId> for u8 {
     }
 }
 
+impl TryFrom<u8> for HardwareId {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x87 => Ok(HardwareId::ATTINY817),
+            0x55 => Ok(HardwareId::SAMD09),
+            id => Err(id),
+        }
+    }
+}
+
+/// The seesaw modules, by their base register address
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum Modules {
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Modules {
     Status = 0x00,
     Gpio = 0x01,
     Sercom0 = 0x02,
@@ -38,3 +53,27 @@ impl Modules {
         self as u8
     }
 }
+
+impl TryFrom<u8> for Modules {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(Modules::Status),
+            0x01 => Ok(Modules::Gpio),
+            0x02 => Ok(Modules::Sercom0),
+            0x08 => Ok(Modules::Timer),
+            0x09 => Ok(Modules::Adc),
+            0x0A => Ok(Modules::Dac),
+            0x0B => Ok(Modules::Interrupt),
+            0x0C => Ok(Modules::Dap),
+            0x0D => Ok(Modules::Eeprom),
+            0x0E => Ok(Modules::Neopixel),
+            0x0F => Ok(Modules::Touch),
+            0x10 => Ok(Modules::Keypad),
+            0x11 => Ok(Modules::Encoder),
+            0x12 => Ok(Modules::Spectrum),
+            base => Err(base),
+        }
+    }
+}
@@ -10,10 +10,76 @@ use crate::{
     },
     seesaw_device, HardwareId, SeesawDeviceInit,
 };
+#[cfg(feature = "async")]
+use crate::{
+    driver::DriverAsync,
+    modules::{
+        encoder::EncoderModuleAsync, gpio::GpioModuleAsync, neopixel::NeopixelModuleAsync,
+        status::StatusModuleAsync,
+    },
+    SeesawDeviceInitAsync,
+};
 
 /// All devices implement the status module
 impl<D: Driver, T: super::SeesawDevice<Driver = D>> StatusModule<D> for T {}
 
+#[cfg(feature = "async")]
+impl<D: DriverAsync, T: super::SeesawDeviceAsync<Driver = D>> StatusModuleAsync<D> for T {}
+
+/// The devices defined in this crate, identified at runtime by their product
+/// ID
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum KnownDevice {
+    ArcadeButton1x4,
+    NeoKey1x4,
+    NeoSlider,
+    RotaryEncoder,
+}
+
+impl KnownDevice {
+    pub const ALL: [KnownDevice; 4] = [
+        KnownDevice::ArcadeButton1x4,
+        KnownDevice::NeoKey1x4,
+        KnownDevice::NeoSlider,
+        KnownDevice::RotaryEncoder,
+    ];
+
+    /// The known device with this product ID, if any
+    pub fn from_product_id(product_id: u16) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|device| device.product_id() == product_id)
+    }
+
+    pub const fn default_addr(self) -> u8 {
+        match self {
+            KnownDevice::ArcadeButton1x4 => ArcadeButton1x4::default_addr(),
+            KnownDevice::NeoKey1x4 => NeoKey1x4::default_addr(),
+            KnownDevice::NeoSlider => NeoSlider::default_addr(),
+            KnownDevice::RotaryEncoder => RotaryEncoder::default_addr(),
+        }
+    }
+
+    pub const fn hardware_id(self) -> HardwareId {
+        match self {
+            KnownDevice::ArcadeButton1x4 => ArcadeButton1x4::hardware_id(),
+            KnownDevice::NeoKey1x4 => NeoKey1x4::hardware_id(),
+            KnownDevice::NeoSlider => NeoSlider::hardware_id(),
+            KnownDevice::RotaryEncoder => RotaryEncoder::hardware_id(),
+        }
+    }
+
+    pub const fn product_id(self) -> u16 {
+        match self {
+            KnownDevice::ArcadeButton1x4 => ArcadeButton1x4::product_id(),
+            KnownDevice::NeoKey1x4 => NeoKey1x4::product_id(),
+            KnownDevice::NeoSlider => NeoSlider::product_id(),
+            KnownDevice::RotaryEncoder => RotaryEncoder::product_id(),
+        }
+    }
+}
+
 seesaw_device! {
     #[doc(hidden)]
     name: GenericDevice,
@@ -29,6 +95,13 @@ impl<D: Driver> SeesawDeviceInit<D> for GenericDevice<D> {
     }
 }
 
+#[cfg(feature = "async")]
+impl<D: DriverAsync> SeesawDeviceInitAsync<D> for GenericDevice<D> {
+    async fn init(mut self) -> Result<Self, Self::Error> {
+        self.reset().await.map(|_| self)
+    }
+}
+
 seesaw_device! {
     /// ArcadeButton1x4
     ///
@@ -64,9 +137,23 @@ impl<D: Driver> SeesawDeviceInit<D> for ArcadeButton1x4<D> {
     }
 }
 
+#[cfg(feature = "async")]
+impl<D: DriverAsync> SeesawDeviceInitAsync<D> for ArcadeButton1x4<D> {
+    async fn init(mut self) -> Result<Self, Self::Error> {
+        self.reset_and_verify_seesaw().await?;
+        self.set_pin_mode_bulk(
+            (1 << 18) | (1 << 19) | (1 << 20) | (1 << 2),
+            PinMode::InputPullup,
+        )
+        .await?;
+        Ok(self)
+    }
+}
+
 impl<D: Driver> ArcadeButton1x4<D> {
     pub fn button_values(&mut self) -> Result<[bool; 4], crate::SeesawError<D::I2cError>> {
-        [18, 19, 20, 2].try_map(|pin| self.digital_read(pin))
+        self.digital_read_bulk()
+            .map(|pins| [18, 19, 20, 2].map(|pin| !matches!(pins >> pin & 0x1, 1)))
     }
 
     /// Set the pin mode of the 4 buttons to input pullup:
@@ -110,6 +197,20 @@ impl<D: Driver> SeesawDeviceInit<D> for NeoKey1x4<D> {
     }
 }
 
+#[cfg(feature = "async")]
+impl<D: DriverAsync> SeesawDeviceInitAsync<D> for NeoKey1x4<D> {
+    async fn init(mut self) -> Result<Self, Self::Error> {
+        self.reset_and_verify_seesaw().await?;
+        self.enable_neopixel().await?;
+        self.set_pin_mode_bulk(
+            (1 << 4) | (1 << 5) | (1 << 6) | (1 << 7),
+            PinMode::InputPullup,
+        )
+        .await?;
+        Ok(self)
+    }
+}
+
 impl<D: Driver> NeoKey1x4<D> {
     pub fn enable_button_pins(&mut self) -> Result<(), crate::SeesawError<D::I2cError>> {
         self.set_pin_mode_bulk(
@@ -144,6 +245,15 @@ impl<D: Driver> SeesawDeviceInit<D> for NeoSlider<D> {
     }
 }
 
+#[cfg(feature = "async")]
+impl<D: DriverAsync> SeesawDeviceInitAsync<D> for NeoSlider<D> {
+    async fn init(mut self) -> Result<Self, Self::Error> {
+        self.reset_and_verify_seesaw().await?;
+        self.enable_neopixel().await?;
+        Ok(self)
+    }
+}
+
 impl<D: Driver> NeoSlider<D> {
     pub fn slider_value(&mut self) -> Result<u16, crate::SeesawError<D::I2cError>> {
         self.analog_read(18)
@@ -171,3 +281,13 @@ impl<D: Driver> SeesawDeviceInit<D> for RotaryEncoder<D> {
             .map(|_| self)
     }
 }
+
+#[cfg(feature = "async")]
+impl<D: DriverAsync> SeesawDeviceInitAsync<D> for RotaryEncoder<D> {
+    async fn init(mut self) -> Result<Self, Self::Error> {
+        self.reset_and_verify_seesaw().await?;
+        self.enable_button().await?;
+        self.enable_neopixel().await?;
+        Ok(self)
+    }
+}
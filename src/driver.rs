@@ -1,7 +1,14 @@
-use crate::common::Reg;
+use crate::{common::Reg, timing::Timing, SeesawError};
 use embedded_hal::blocking::{delay, i2c};
 
-const DELAY_TIME: u32 = 125;
+/// The delay used by [`DriverExt`] on a bare driver, which doesn't know which
+/// device it is talking to
+const DELAY_TIME: u32 = Timing::DEFAULT.read_delay_us;
+
+/// The largest payload, in bytes, that fits in a single register write. The
+/// seesaw firmware receives into a 32 byte buffer, not counting the 2 byte
+/// register address.
+pub const MAX_WRITE_LEN: usize = 32;
 
 /// Blanket trait for something that implements I2C bus operations, with a
 /// combined Error associated type
@@ -22,6 +29,29 @@ where
 pub trait Driver: I2cDriver + delay::DelayUs<u32> {}
 impl<T> Driver for T where T: I2cDriver + delay::DelayUs<u32> {}
 
+/// Log a register access at the trace level, if the `defmt` feature is
+/// enabled
+macro_rules! trace_register {
+    (read, $addr:expr, $reg:expr, $bytes:expr) => {
+        #[cfg(feature = "defmt")]
+        defmt::trace!(
+            "seesaw {=u8:#04x} read {=[u8]:#04x}: {=[u8]:#04x}",
+            $addr,
+            &$reg[..],
+            &$bytes[..]
+        );
+    };
+    (write, $addr:expr, $reg:expr, $bytes:expr) => {
+        #[cfg(feature = "defmt")]
+        defmt::trace!(
+            "seesaw {=u8:#04x} write {=[u8]:#04x}: {=[u8]:#04x}",
+            $addr,
+            &$reg[..],
+            &$bytes[..]
+        );
+    };
+}
+
 macro_rules! impl_integer_write {
     ($fn:ident $nty:tt) => {
         fn $fn(
@@ -31,6 +61,7 @@ macro_rules! impl_integer_write {
             value: $nty,
         ) -> Result<(), Self::Error> {
             self.register_write(addr, reg, &<$nty>::to_be_bytes(value))
+                .map_err(bus_error)
         }
     };
 }
@@ -53,14 +84,15 @@ pub trait DriverExt {
         reg: &Reg,
     ) -> Result<[u8; N], Self::Error>;
 
-    fn register_write<const N: usize>(
+    /// Write `bytes` to the register `reg`. Bus errors come back as
+    /// [`SeesawError::I2c`], and more than [`MAX_WRITE_LEN`] bytes as
+    /// [`SeesawError::WriteTooLong`] without touching the bus.
+    fn register_write(
         &mut self,
         addr: i2c::SevenBitAddress,
         reg: &Reg,
-        bytes: &[u8; N],
-    ) -> Result<(), Self::Error>
-    where
-        [(); N + 2]: Sized;
+        bytes: &[u8],
+    ) -> Result<(), SeesawError<Self::Error>>;
 
     impl_integer_read! { read_u8 u8 }
     impl_integer_read! { read_u16 u16 }
@@ -88,28 +120,289 @@ impl<T: Driver> DriverExt for T {
         addr: i2c::SevenBitAddress,
         reg: &Reg,
     ) -> Result<[u8; N], Self::Error> {
-        let mut buffer = [0u8; N];
-        self.write(addr, reg)?;
-        self.delay_us(DELAY_TIME);
-        self.read(addr, &mut buffer)?;
-        Ok(buffer)
+        read_register(self, addr, reg, DELAY_TIME)
+    }
+
+    fn register_write(
+        &mut self,
+        addr: i2c::SevenBitAddress,
+        reg: &Reg,
+        bytes: &[u8],
+    ) -> Result<(), SeesawError<Self::Error>> {
+        write_register(self, addr, reg, bytes, DELAY_TIME)
+    }
+}
+
+/// A driver whose register reads and writes wait for the delays in a
+/// [`Timing`] profile, rather than the fixed delay used by [`DriverExt`] on a
+/// bare driver. Devices hand one out from [`SeesawDevice::bus`].
+///
+/// [`SeesawDevice::bus`]: crate::SeesawDevice::bus
+#[derive(Debug)]
+pub struct TimedDriver<'a, D> {
+    driver: &'a mut D,
+    read_delay_us: u32,
+    write_delay_us: u32,
+}
+
+impl<'a, D> TimedDriver<'a, D> {
+    pub fn new(driver: &'a mut D, timing: &Timing) -> Self {
+        Self {
+            driver,
+            read_delay_us: timing.read_delay_us,
+            write_delay_us: timing.write_delay_us,
+        }
+    }
+
+    /// Wait `us` between writing a register's address and reading it back,
+    /// instead of the profile's read delay
+    pub fn with_read_delay(self, us: u32) -> Self {
+        Self {
+            read_delay_us: us,
+            ..self
+        }
+    }
+}
+
+impl<D: Driver> DriverExt for TimedDriver<'_, D> {
+    type Error = D::I2cError;
+
+    fn register_read<const N: usize>(
+        &mut self,
+        addr: i2c::SevenBitAddress,
+        reg: &Reg,
+    ) -> Result<[u8; N], Self::Error> {
+        read_register(self.driver, addr, reg, self.read_delay_us)
     }
 
-    fn register_write<const N: usize>(
+    fn register_write(
         &mut self,
         addr: i2c::SevenBitAddress,
         reg: &Reg,
-        bytes: &[u8; N],
-    ) -> Result<(), Self::Error>
-    where
-        [(); N + 2]: Sized,
-    {
-        let mut buffer = [0u8; N + 2];
-        buffer[0..2].copy_from_slice(reg);
-        buffer[2..].copy_from_slice(bytes);
-
-        self.write(addr, &buffer)?;
-        self.delay_us(DELAY_TIME);
+        bytes: &[u8],
+    ) -> Result<(), SeesawError<Self::Error>> {
+        write_register(self.driver, addr, reg, bytes, self.write_delay_us)
+    }
+}
+
+fn read_register<D: Driver, const N: usize>(
+    driver: &mut D,
+    addr: i2c::SevenBitAddress,
+    reg: &Reg,
+    delay_us: u32,
+) -> Result<[u8; N], D::I2cError> {
+    let mut buffer = [0u8; N];
+    driver.write(addr, reg)?;
+    driver.delay_us(delay_us);
+    driver.read(addr, &mut buffer)?;
+    trace_register!(read, addr, reg, buffer);
+    Ok(buffer)
+}
+
+fn write_register<D: Driver>(
+    driver: &mut D,
+    addr: i2c::SevenBitAddress,
+    reg: &Reg,
+    bytes: &[u8],
+    delay_us: u32,
+) -> Result<(), SeesawError<D::I2cError>> {
+    let buffer = write_buffer(reg, bytes)?;
+    driver
+        .write(addr, buffer.as_slice())
+        .map_err(|err| SeesawError::i2c(reg)(err.into()))?;
+    trace_register!(write, addr, reg, bytes);
+    driver.delay_us(delay_us);
+    Ok(())
+}
+
+/// The register address followed by `bytes`, ready to go on the bus
+struct WriteBuffer {
+    bytes: [u8; MAX_WRITE_LEN + 2],
+    len: usize,
+}
+
+impl WriteBuffer {
+    fn as_slice(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+fn write_buffer<E>(reg: &Reg, bytes: &[u8]) -> Result<WriteBuffer, SeesawError<E>> {
+    if bytes.len() > MAX_WRITE_LEN {
+        return Err(SeesawError::WriteTooLong {
+            reg: *reg,
+            len: bytes.len(),
+        });
+    }
+    let mut buffer = WriteBuffer {
+        bytes: [0; MAX_WRITE_LEN + 2],
+        len: bytes.len() + 2,
+    };
+    buffer.bytes[0..2].copy_from_slice(reg);
+    buffer.bytes[2..buffer.len].copy_from_slice(bytes);
+    Ok(buffer)
+}
+
+/// The bus error from a write that can't be too long
+fn bus_error<E>(err: SeesawError<E>) -> E {
+    match err {
+        SeesawError::I2c { source, .. } => source,
+        _ => unreachable!("fixed size writes fit in one register write"),
+    }
+}
+
+#[cfg(feature = "async")]
+pub use self::asynch::*;
+
+#[cfg(feature = "async")]
+mod asynch {
+    use super::{bus_error, write_buffer, TimedDriver, DELAY_TIME};
+    use crate::{common::Reg, SeesawError};
+    use embedded_hal_async::{
+        delay::DelayNs,
+        i2c::{ErrorType, I2c, SevenBitAddress},
+    };
+
+    /// Async counterpart of [`Driver`](super::Driver): something that
+    /// implements both the async I2C and delay traits
+    pub trait DriverAsync: I2c + DelayNs {}
+    impl<T> DriverAsync for T where T: I2c + DelayNs {}
+
+    macro_rules! impl_integer_write_async {
+        ($fn:ident $nty:tt) => {
+            async fn $fn(
+                &mut self,
+                addr: SevenBitAddress,
+                reg: &Reg,
+                value: $nty,
+            ) -> Result<(), Self::Error> {
+                self.register_write(addr, reg, &<$nty>::to_be_bytes(value))
+                    .await
+                    .map_err(bus_error)
+            }
+        };
+    }
+
+    macro_rules! impl_integer_read_async {
+        ($fn:ident $nty:tt) => {
+            async fn $fn(&mut self, addr: SevenBitAddress, reg: &Reg) -> Result<$nty, Self::Error> {
+                self.register_read::<{ ($nty::BITS / 8) as usize }>(addr, reg)
+                    .await
+                    .map($nty::from_be_bytes)
+            }
+        };
+    }
+
+    /// Async counterpart of [`DriverExt`](super::DriverExt). The delay between
+    /// writing a register address and reading it back yields to the executor.
+    pub trait DriverExtAsync {
+        type Error;
+
+        async fn register_read<const N: usize>(
+            &mut self,
+            addr: SevenBitAddress,
+            reg: &Reg,
+        ) -> Result<[u8; N], Self::Error>;
+
+        /// Write `bytes` to the register `reg`. Bus errors come back as
+        /// [`SeesawError::I2c`], and more than
+        /// [`MAX_WRITE_LEN`](super::MAX_WRITE_LEN) bytes as
+        /// [`SeesawError::WriteTooLong`] without touching the bus.
+        async fn register_write(
+            &mut self,
+            addr: SevenBitAddress,
+            reg: &Reg,
+            bytes: &[u8],
+        ) -> Result<(), SeesawError<Self::Error>>;
+
+        impl_integer_read_async! { read_u8 u8 }
+        impl_integer_read_async! { read_u16 u16 }
+        impl_integer_read_async! { read_u32 u32 }
+        impl_integer_read_async! { read_u64 u64 }
+        impl_integer_read_async! { read_i8 i8 }
+        impl_integer_read_async! { read_i16 i16 }
+        impl_integer_read_async! { read_i32 i32 }
+        impl_integer_read_async! { read_i64 i64 }
+        impl_integer_write_async! { write_u8 u8 }
+        impl_integer_write_async! { write_u16 u16 }
+        impl_integer_write_async! { write_u32 u32 }
+        impl_integer_write_async! { write_u64 u64 }
+        impl_integer_write_async! { write_i8 i8 }
+        impl_integer_write_async! { write_i16 i16 }
+        impl_integer_write_async! { write_i32 i32 }
+        impl_integer_write_async! { write_i64 i64 }
+    }
+
+    impl<T: DriverAsync> DriverExtAsync for T {
+        type Error = <T as ErrorType>::Error;
+
+        async fn register_read<const N: usize>(
+            &mut self,
+            addr: SevenBitAddress,
+            reg: &Reg,
+        ) -> Result<[u8; N], Self::Error> {
+            read_register(self, addr, reg, DELAY_TIME).await
+        }
+
+        async fn register_write(
+            &mut self,
+            addr: SevenBitAddress,
+            reg: &Reg,
+            bytes: &[u8],
+        ) -> Result<(), SeesawError<Self::Error>> {
+            write_register(self, addr, reg, bytes, DELAY_TIME).await
+        }
+    }
+
+    impl<D: DriverAsync> DriverExtAsync for TimedDriver<'_, D> {
+        type Error = <D as ErrorType>::Error;
+
+        async fn register_read<const N: usize>(
+            &mut self,
+            addr: SevenBitAddress,
+            reg: &Reg,
+        ) -> Result<[u8; N], Self::Error> {
+            read_register(self.driver, addr, reg, self.read_delay_us).await
+        }
+
+        async fn register_write(
+            &mut self,
+            addr: SevenBitAddress,
+            reg: &Reg,
+            bytes: &[u8],
+        ) -> Result<(), SeesawError<Self::Error>> {
+            write_register(self.driver, addr, reg, bytes, self.write_delay_us).await
+        }
+    }
+
+    async fn read_register<D: DriverAsync, const N: usize>(
+        driver: &mut D,
+        addr: SevenBitAddress,
+        reg: &Reg,
+        delay_us: u32,
+    ) -> Result<[u8; N], D::Error> {
+        let mut buffer = [0u8; N];
+        driver.write(addr, reg).await?;
+        driver.delay_us(delay_us).await;
+        driver.read(addr, &mut buffer).await?;
+        trace_register!(read, addr, reg, buffer);
+        Ok(buffer)
+    }
+
+    async fn write_register<D: DriverAsync>(
+        driver: &mut D,
+        addr: SevenBitAddress,
+        reg: &Reg,
+        bytes: &[u8],
+        delay_us: u32,
+    ) -> Result<(), SeesawError<D::Error>> {
+        let buffer = write_buffer(reg, bytes)?;
+        driver
+            .write(addr, buffer.as_slice())
+            .await
+            .map_err(SeesawError::i2c(reg))?;
+        trace_register!(write, addr, reg, bytes);
+        driver.delay_us(delay_us).await;
         Ok(())
     }
 }
@@ -0,0 +1,683 @@
+use crate::{
+    bus::DiscoveredDevice,
+    devices::{ArcadeButton1x4, KnownDevice, NeoKey1x4, NeoSlider, RotaryEncoder},
+    driver::{Driver, DriverExt, TimedDriver},
+    modules::{
+        adc::{self, AdcModule},
+        encoder::{self, EncoderModule},
+        gpio::{self, GpioModule, PinMode},
+        neopixel::{self, NeopixelModule, PixelFormat},
+        status::{self, DeviceCapabilities, ProductDateCode, StatusModule},
+        timer::{self, TimerModule},
+    },
+    HardwareId, Modules, PinCapabilities, Rgb, SeesawDevice, SeesawDeviceInit, SeesawError, Timing,
+};
+
+/// Dispatch to every variant of `AnyDevice`
+macro_rules! each {
+    ($self:ident, |$dev:ident| $body:expr) => {
+        match $self {
+            AnyDevice::ArcadeButton1x4($dev) => $body,
+            AnyDevice::NeoKey1x4($dev) => $body,
+            AnyDevice::NeoSlider($dev) => $body,
+            AnyDevice::RotaryEncoder($dev) => $body,
+        }
+    };
+}
+
+/// Dispatch to the variants of `AnyDevice` that implement a module, and
+/// reject the rest with `SeesawError::ModuleNotSupported`
+macro_rules! dispatch {
+    ($self:ident, $module:expr, [$($variant:ident),*], |$dev:ident| $body:expr) => {
+        match $self {
+            $(AnyDevice::$variant($dev) => $body,)*
+            _ => Err(SeesawError::ModuleNotSupported($module)),
+        }
+    };
+}
+
+/// Any of the devices defined in [`devices`](crate::devices), chosen at
+/// runtime.
+///
+/// Every device implements the status and GPIO modules. Calls to a module the
+/// device doesn't have return [`SeesawError::ModuleNotSupported`].
+#[derive(Debug)]
+pub enum AnyDevice<D> {
+    ArcadeButton1x4(ArcadeButton1x4<D>),
+    NeoKey1x4(NeoKey1x4<D>),
+    NeoSlider(NeoSlider<D>),
+    RotaryEncoder(RotaryEncoder<D>),
+}
+
+impl<D: Driver> AnyDevice<D> {
+    pub fn new(device: KnownDevice, addr: u8, driver: D) -> Self {
+        match device {
+            KnownDevice::ArcadeButton1x4 => {
+                Self::ArcadeButton1x4(ArcadeButton1x4::new(addr, driver))
+            }
+            KnownDevice::NeoKey1x4 => Self::NeoKey1x4(NeoKey1x4::new(addr, driver)),
+            KnownDevice::NeoSlider => Self::NeoSlider(NeoSlider::new(addr, driver)),
+            KnownDevice::RotaryEncoder => Self::RotaryEncoder(RotaryEncoder::new(addr, driver)),
+        }
+    }
+
+    pub fn new_with_default_addr(device: KnownDevice, driver: D) -> Self {
+        Self::new(device, device.default_addr(), driver)
+    }
+
+    /// Create the device with a [`Timing`] profile other than the default for
+    /// its hardware ID
+    pub fn new_with_timing(device: KnownDevice, addr: u8, driver: D, timing: Timing) -> Self {
+        match device {
+            KnownDevice::ArcadeButton1x4 => {
+                Self::ArcadeButton1x4(ArcadeButton1x4::new_with_timing(addr, driver, timing))
+            }
+            KnownDevice::NeoKey1x4 => {
+                Self::NeoKey1x4(NeoKey1x4::new_with_timing(addr, driver, timing))
+            }
+            KnownDevice::NeoSlider => {
+                Self::NeoSlider(NeoSlider::new_with_timing(addr, driver, timing))
+            }
+            KnownDevice::RotaryEncoder => {
+                Self::RotaryEncoder(RotaryEncoder::new_with_timing(addr, driver, timing))
+            }
+        }
+    }
+
+    /// Create the device found by [`bus::scan`](crate::bus::scan), if it is a
+    /// known one
+    pub fn from_discovered(found: &DiscoveredDevice, driver: D) -> Option<Self> {
+        found
+            .device
+            .map(|device| Self::new(device, found.addr, driver))
+    }
+
+    /// Run the device's [`SeesawDeviceInit::init`]
+    pub fn init(self) -> Result<Self, SeesawError<D::I2cError>> {
+        match self {
+            Self::ArcadeButton1x4(dev) => dev.init().map(Self::ArcadeButton1x4),
+            Self::NeoKey1x4(dev) => dev.init().map(Self::NeoKey1x4),
+            Self::NeoSlider(dev) => dev.init().map(Self::NeoSlider),
+            Self::RotaryEncoder(dev) => dev.init().map(Self::RotaryEncoder),
+        }
+    }
+
+    pub fn kind(&self) -> KnownDevice {
+        match self {
+            Self::ArcadeButton1x4(_) => KnownDevice::ArcadeButton1x4,
+            Self::NeoKey1x4(_) => KnownDevice::NeoKey1x4,
+            Self::NeoSlider(_) => KnownDevice::NeoSlider,
+            Self::RotaryEncoder(_) => KnownDevice::RotaryEncoder,
+        }
+    }
+
+    /// Whether the device has `module`
+    pub fn supports(&self, module: Modules) -> bool {
+        use KnownDevice::*;
+        match module {
+            Modules::Status | Modules::Gpio => true,
+            Modules::Adc => matches!(self.kind(), NeoSlider),
+            Modules::Timer => matches!(self.kind(), ArcadeButton1x4),
+            Modules::Neopixel => matches!(self.kind(), NeoKey1x4 | NeoSlider | RotaryEncoder),
+            Modules::Encoder => matches!(self.kind(), RotaryEncoder),
+            _ => false,
+        }
+    }
+
+    pub fn addr(&self) -> u8 {
+        each!(self, |dev| dev.addr())
+    }
+
+    pub fn driver(&mut self) -> &mut D {
+        each!(self, |dev| dev.driver())
+    }
+
+    pub fn timing(&self) -> &Timing {
+        each!(self, |dev| dev.timing())
+    }
+
+    // Status module
+
+    pub fn capabilities(&mut self) -> Result<DeviceCapabilities, SeesawError<D::I2cError>> {
+        each!(self, |dev| dev.capabilities())
+    }
+
+    pub fn hardware_id(&mut self) -> Result<u8, SeesawError<D::I2cError>> {
+        each!(self, |dev| dev.hardware_id())
+    }
+
+    pub fn product_info(&mut self) -> Result<ProductDateCode, SeesawError<D::I2cError>> {
+        each!(self, |dev| dev.product_info())
+    }
+
+    pub fn reset(&mut self) -> Result<(), SeesawError<D::I2cError>> {
+        each!(self, |dev| dev.reset())
+    }
+
+    pub fn verify_product_id(&mut self) -> Result<(), SeesawError<D::I2cError>> {
+        each!(self, |dev| dev.verify_product_id())
+    }
+
+    pub fn temp(&mut self) -> Result<f32, SeesawError<D::I2cError>> {
+        each!(self, |dev| dev.temp())
+    }
+
+    // GPIO module
+
+    pub fn digital_read(&mut self, pin: u8) -> Result<bool, SeesawError<D::I2cError>> {
+        each!(self, |dev| dev.digital_read(pin))
+    }
+
+    pub fn digital_read_bulk(&mut self) -> Result<u32, SeesawError<D::I2cError>> {
+        each!(self, |dev| dev.digital_read_bulk())
+    }
+
+    pub fn digital_write(&mut self, pin: u8, value: bool) -> Result<(), SeesawError<D::I2cError>> {
+        each!(self, |dev| dev.digital_write(pin, value))
+    }
+
+    pub fn digital_write_bulk(
+        &mut self,
+        pins: u32,
+        value: bool,
+    ) -> Result<(), SeesawError<D::I2cError>> {
+        each!(self, |dev| dev.digital_write_bulk(pins, value))
+    }
+
+    pub fn set_pin_mode(&mut self, pin: u8, mode: PinMode) -> Result<(), SeesawError<D::I2cError>> {
+        each!(self, |dev| dev.set_pin_mode(pin, mode))
+    }
+
+    pub fn set_pin_mode_bulk(
+        &mut self,
+        pins: u32,
+        mode: PinMode,
+    ) -> Result<(), SeesawError<D::I2cError>> {
+        each!(self, |dev| dev.set_pin_mode_bulk(pins, mode))
+    }
+
+    // ADC module
+
+    pub fn analog_read(&mut self, pin: u8) -> Result<u16, SeesawError<D::I2cError>> {
+        dispatch!(self, Modules::Adc, [NeoSlider], |dev| dev.analog_read(pin))
+    }
+
+    // Timer module
+
+    pub fn analog_write(&mut self, pin: u8, value: u8) -> Result<(), SeesawError<D::I2cError>> {
+        dispatch!(self, Modules::Timer, [ArcadeButton1x4], |dev| dev
+            .analog_write(pin, value))
+    }
+
+    // NeoPixel module
+
+    /// The number of neopixels on the device, or 0 if it has none
+    pub fn n_leds(&self) -> u16 {
+        match self {
+            Self::NeoKey1x4(_) => <NeoKey1x4<D> as NeopixelModule<D>>::N_LEDS,
+            Self::NeoSlider(_) => <NeoSlider<D> as NeopixelModule<D>>::N_LEDS,
+            Self::RotaryEncoder(_) => <RotaryEncoder<D> as NeopixelModule<D>>::N_LEDS,
+            Self::ArcadeButton1x4(_) => 0,
+        }
+    }
+
+    pub fn enable_neopixel(&mut self) -> Result<(), SeesawError<D::I2cError>> {
+        dispatch!(
+            self,
+            Modules::Neopixel,
+            [NeoKey1x4, NeoSlider, RotaryEncoder],
+            |dev| dev.enable_neopixel()
+        )
+    }
+
+    pub fn neopixel_brightness(&self) -> Result<u8, SeesawError<D::I2cError>> {
+        dispatch!(
+            self,
+            Modules::Neopixel,
+            [NeoKey1x4, NeoSlider, RotaryEncoder],
+            |dev| Ok(dev.neopixel_brightness())
+        )
+    }
+
+    pub fn set_neopixel_brightness(
+        &mut self,
+        brightness: u8,
+    ) -> Result<(), SeesawError<D::I2cError>> {
+        dispatch!(
+            self,
+            Modules::Neopixel,
+            [NeoKey1x4, NeoSlider, RotaryEncoder],
+            |dev| {
+                dev.set_neopixel_brightness(brightness);
+                Ok(())
+            }
+        )
+    }
+
+    pub fn set_neopixel_color(
+        &mut self,
+        r: u8,
+        g: u8,
+        b: u8,
+    ) -> Result<(), SeesawError<D::I2cError>> {
+        dispatch!(
+            self,
+            Modules::Neopixel,
+            [NeoKey1x4, NeoSlider, RotaryEncoder],
+            |dev| dev.set_neopixel_color(r, g, b)
+        )
+    }
+
+    pub fn set_nth_neopixel_color(
+        &mut self,
+        n: u16,
+        r: u8,
+        g: u8,
+        b: u8,
+    ) -> Result<(), SeesawError<D::I2cError>> {
+        dispatch!(
+            self,
+            Modules::Neopixel,
+            [NeoKey1x4, NeoSlider, RotaryEncoder],
+            |dev| dev.set_nth_neopixel_color(n, r, g, b)
+        )
+    }
+
+    pub fn set_neopixel_colors(
+        &mut self,
+        colors: &[(u8, u8, u8)],
+    ) -> Result<(), SeesawError<D::I2cError>> {
+        dispatch!(
+            self,
+            Modules::Neopixel,
+            [NeoKey1x4, NeoSlider, RotaryEncoder],
+            |dev| dev.set_neopixel_colors(colors)
+        )
+    }
+
+    pub fn sync_neopixel(&mut self) -> Result<(), SeesawError<D::I2cError>> {
+        dispatch!(
+            self,
+            Modules::Neopixel,
+            [NeoKey1x4, NeoSlider, RotaryEncoder],
+            |dev| dev.sync_neopixel()
+        )
+    }
+
+    // Encoder module
+
+    pub fn button(&mut self) -> Result<bool, SeesawError<D::I2cError>> {
+        dispatch!(self, Modules::Encoder, [RotaryEncoder], |dev| dev.button())
+    }
+
+    pub fn delta(&mut self) -> Result<i32, SeesawError<D::I2cError>> {
+        dispatch!(self, Modules::Encoder, [RotaryEncoder], |dev| dev.delta())
+    }
+
+    pub fn position(&mut self) -> Result<i32, SeesawError<D::I2cError>> {
+        dispatch!(self, Modules::Encoder, [RotaryEncoder], |dev| dev
+            .position())
+    }
+
+    pub fn set_position(&mut self, pos: i32) -> Result<(), SeesawError<D::I2cError>> {
+        dispatch!(self, Modules::Encoder, [RotaryEncoder], |dev| dev
+            .set_position(pos))
+    }
+}
+
+/// A seesaw device that isn't defined with
+/// [`seesaw_device!`](crate::seesaw_device), such as a prototype board.
+///
+/// The hardware ID, product info and capabilities are read from the device
+/// by [`DynamicDevice::init`], and calls to a module the device didn't report
+/// return [`SeesawError::ModuleNotSupported`].
+#[derive(Debug)]
+pub struct DynamicDevice<D> {
+    addr: u8,
+    driver: D,
+    hardware_id: HardwareId,
+    product_info: ProductDateCode,
+    capabilities: DeviceCapabilities,
+    timing: Timing,
+    n_leds: u16,
+    brightness: u8,
+}
+
+impl<D: Driver> DynamicDevice<D> {
+    /// Reset the device at `addr` and read what it is and which modules it
+    /// has. The device then uses the default [`Timing`] for its hardware ID.
+    pub fn init(addr: u8, driver: D) -> Result<Self, SeesawError<D::I2cError>> {
+        Self::init_with_timing(addr, driver, Timing::DEFAULT).map(|dev| {
+            let timing = Timing::for_hardware(dev.hardware_id);
+            dev.with_timing(timing)
+        })
+    }
+
+    /// Like [`DynamicDevice::init`], but using `timing` from the reset on
+    pub fn init_with_timing(
+        addr: u8,
+        mut driver: D,
+        timing: Timing,
+    ) -> Result<Self, SeesawError<D::I2cError>> {
+        let mut bus = TimedDriver::new(&mut driver, &timing);
+        bus.write_u8(addr, status::STATUS_SWRST, 0xFF)
+            .map_err(SeesawError::i2c(status::STATUS_SWRST))?;
+        driver.delay_us(timing.reset_delay_us);
+        let mut bus = TimedDriver::new(&mut driver, &timing);
+        let hardware_id = bus
+            .read_u8(addr, status::STATUS_HW_ID)
+            .map_err(SeesawError::i2c(status::STATUS_HW_ID))
+            .and_then(|id| HardwareId::try_from(id).map_err(SeesawError::InvalidHardwareId))?;
+        let product_info = bus
+            .read_u32(addr, status::STATUS_VERSION)
+            .map(ProductDateCode::from)
+            .map_err(SeesawError::i2c(status::STATUS_VERSION))?;
+        let capabilities = bus
+            .read_u32(addr, status::STATUS_OPTIONS)
+            .map(DeviceCapabilities::from)
+            .map_err(SeesawError::i2c(status::STATUS_OPTIONS))?;
+
+        Ok(Self {
+            addr,
+            driver,
+            hardware_id,
+            product_info,
+            capabilities,
+            timing,
+            n_leds: 0,
+            brightness: 255,
+        })
+    }
+
+    /// Create the device found by [`bus::scan`](crate::bus::scan), without
+    /// reading it again
+    pub fn from_discovered(found: &DiscoveredDevice, driver: D) -> Self {
+        Self {
+            addr: found.addr,
+            driver,
+            hardware_id: found.hardware_id,
+            product_info: found.date_code,
+            capabilities: found.capabilities,
+            timing: Timing::for_hardware(found.hardware_id),
+            n_leds: 0,
+            brightness: 255,
+        }
+    }
+
+    /// Replace the device's [`Timing`] profile
+    pub fn with_timing(self, timing: Timing) -> Self {
+        Self { timing, ..self }
+    }
+
+    pub fn addr(&self) -> u8 {
+        self.addr
+    }
+
+    pub fn driver(&mut self) -> &mut D {
+        &mut self.driver
+    }
+
+    pub fn release(self) -> D {
+        self.driver
+    }
+
+    pub fn timing(&self) -> &Timing {
+        &self.timing
+    }
+
+    /// The driver, with register reads and writes paced by the device's
+    /// [`Timing`]
+    fn bus(&mut self) -> TimedDriver<'_, D> {
+        TimedDriver::new(&mut self.driver, &self.timing)
+    }
+
+    pub fn hardware_id(&self) -> HardwareId {
+        self.hardware_id
+    }
+
+    pub fn product_info(&self) -> ProductDateCode {
+        self.product_info
+    }
+
+    pub fn capabilities(&self) -> DeviceCapabilities {
+        self.capabilities
+    }
+
+    /// Whether the device reported having `module`
+    pub fn supports(&self, module: Modules) -> bool {
+        let caps = &self.capabilities;
+        match module {
+            Modules::Status => caps.status,
+            Modules::Gpio => caps.gpio,
+            Modules::Sercom0 => caps.sercom0,
+            Modules::Timer => caps.timer,
+            Modules::Adc => caps.adc,
+            Modules::Dac => caps.dac,
+            Modules::Interrupt => caps.interrupt,
+            Modules::Dap => caps.dap,
+            Modules::Eeprom => caps.eeprom,
+            Modules::Neopixel => caps.neopixel,
+            Modules::Touch => caps.touch,
+            Modules::Keypad => caps.keypad,
+            Modules::Encoder => caps.encoder,
+            Modules::Spectrum => caps.spectrum,
+        }
+    }
+
+    fn require(&self, module: Modules) -> Result<(), SeesawError<D::I2cError>> {
+        match self.supports(module) {
+            true => Ok(()),
+            false => Err(SeesawError::ModuleNotSupported(module)),
+        }
+    }
+
+    // Status module
+
+    pub fn reset(&mut self) -> Result<(), SeesawError<D::I2cError>> {
+        let addr = self.addr;
+        self.bus()
+            .write_u8(addr, status::STATUS_SWRST, 0xFF)
+            .map(|_| self.driver.delay_us(self.timing.reset_delay_us))
+            .map_err(SeesawError::i2c(status::STATUS_SWRST))
+    }
+
+    pub fn temp(&mut self) -> Result<f32, SeesawError<D::I2cError>> {
+        let addr = self.addr;
+        self.bus()
+            .read_u32(addr, status::STATUS_TEMP)
+            .map(|buf| buf as f32 / (1u32 << 16) as f32)
+            .map_err(SeesawError::i2c(status::STATUS_TEMP))
+    }
+
+    // GPIO module
+
+    pub fn digital_read(&mut self, pin: u8) -> Result<bool, SeesawError<D::I2cError>> {
+        let mask = gpio::pin_mask(self.hardware_id, pin)?;
+        self.digital_read_bulk().map(|pins| pins & mask == 0)
+    }
+
+    pub fn digital_read_bulk(&mut self) -> Result<u32, SeesawError<D::I2cError>> {
+        self.require(Modules::Gpio)?;
+        let addr = self.addr;
+        self.bus()
+            .read_u32(addr, gpio::GPIO)
+            .map_err(SeesawError::i2c(gpio::GPIO))
+    }
+
+    pub fn digital_write(&mut self, pin: u8, value: bool) -> Result<(), SeesawError<D::I2cError>> {
+        let mask = gpio::pin_mask(self.hardware_id, pin)?;
+        self.digital_write_bulk(mask, value)
+    }
+
+    pub fn digital_write_bulk(
+        &mut self,
+        pins: u32,
+        value: bool,
+    ) -> Result<(), SeesawError<D::I2cError>> {
+        self.require(Modules::Gpio)?;
+        gpio::check_pins(self.hardware_id, pins)?;
+        let addr = self.addr;
+        let reg = if value { gpio::SET_HIGH } else { gpio::SET_LOW };
+        self.bus()
+            .write_u32(addr, reg, pins)
+            .map_err(SeesawError::i2c(reg))
+    }
+
+    pub fn set_pin_mode(&mut self, pin: u8, mode: PinMode) -> Result<(), SeesawError<D::I2cError>> {
+        let mask = gpio::pin_mask(self.hardware_id, pin)?;
+        self.set_pin_mode_bulk(mask, mode)
+    }
+
+    pub fn set_pin_mode_bulk(
+        &mut self,
+        pins: u32,
+        mode: PinMode,
+    ) -> Result<(), SeesawError<D::I2cError>> {
+        self.require(Modules::Gpio)?;
+        gpio::check_pins(self.hardware_id, pins)?;
+        let addr = self.addr;
+        gpio::write_pin_mode(&mut self.bus(), addr, pins, mode)
+    }
+
+    // ADC module
+
+    pub fn analog_read(&mut self, pin: u8) -> Result<u16, SeesawError<D::I2cError>> {
+        self.require(Modules::Adc)?;
+        let addr = self.addr;
+        let delay = self.timing.adc_delay_us;
+        let reg = adc::channel_reg(self.hardware_id, pin)?;
+        self.bus()
+            .with_read_delay(delay)
+            .read_u16(addr, &reg)
+            .map_err(SeesawError::i2c(&reg))
+    }
+
+    // Timer module
+
+    pub fn analog_write(&mut self, pin: u8, value: u8) -> Result<(), SeesawError<D::I2cError>> {
+        self.require(Modules::Timer)?;
+        let addr = self.addr;
+        let channel = timer::pwm_channel(self.hardware_id, pin)?;
+        self.bus()
+            .write_u16(addr, timer::PWM_VAL, u16::from_be_bytes([channel, value]))
+            .map_err(SeesawError::i2c(timer::PWM_VAL))
+    }
+
+    // NeoPixel module
+
+    /// The number of neopixels set by [`DynamicDevice::enable_neopixel`]
+    pub fn n_leds(&self) -> u16 {
+        self.n_leds
+    }
+
+    /// How bright the pixels are, out of 255. Every color written is scaled by
+    /// it.
+    pub fn neopixel_brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    pub fn set_neopixel_brightness(&mut self, brightness: u8) {
+        self.brightness = brightness;
+    }
+
+    /// Drive `n_leds` neopixels from `pin`. Unlike a device's
+    /// [`NeopixelModule`], the pin and length aren't known ahead of time, so
+    /// a strip longer than the chip's pixel buffer returns
+    /// [`SeesawError::PixelOutOfRange`].
+    pub fn enable_neopixel(
+        &mut self,
+        pin: u8,
+        n_leds: u16,
+    ) -> Result<(), SeesawError<D::I2cError>> {
+        self.require(Modules::Neopixel)?;
+        if !PinCapabilities::for_hardware(self.hardware_id).is_digital(pin) {
+            return Err(SeesawError::InvalidPin {
+                module: Modules::Neopixel,
+                pin,
+            });
+        }
+        neopixel::check_len(n_leds as usize, neopixel::max_pixels(self.hardware_id))?;
+        let addr = self.addr;
+        self.bus()
+            .write_u8(addr, neopixel::SET_PIN, pin)
+            .map_err(SeesawError::i2c(neopixel::SET_PIN))?;
+        self.driver.delay_us(self.timing.neopixel_delay_us);
+        self.bus()
+            .write_u16(addr, neopixel::SET_LEN, 3 * n_leds)
+            .map(|_| {
+                self.driver.delay_us(self.timing.neopixel_delay_us);
+                self.n_leds = n_leds;
+            })
+            .map_err(SeesawError::i2c(neopixel::SET_LEN))
+    }
+
+    pub fn set_neopixel_color(
+        &mut self,
+        r: u8,
+        g: u8,
+        b: u8,
+    ) -> Result<(), SeesawError<D::I2cError>> {
+        self.set_nth_neopixel_color(0, r, g, b)
+    }
+
+    pub fn set_nth_neopixel_color(
+        &mut self,
+        n: u16,
+        r: u8,
+        g: u8,
+        b: u8,
+    ) -> Result<(), SeesawError<D::I2cError>> {
+        self.require(Modules::Neopixel)?;
+        let addr = self.addr;
+        neopixel::check_index(n, self.n_leds)?;
+        let [zero, one] = u16::to_be_bytes(3 * n);
+        let Rgb { r, g, b } = Rgb::new(r, g, b).scale(self.brightness);
+        self.bus()
+            .register_write(addr, neopixel::SET_BUF, &[zero, one, r, g, b])
+    }
+
+    pub fn set_neopixel_colors(
+        &mut self,
+        colors: &[(u8, u8, u8)],
+    ) -> Result<(), SeesawError<D::I2cError>> {
+        self.require(Modules::Neopixel)?;
+        let addr = self.addr;
+        neopixel::check_len(colors.len(), self.n_leds)?;
+        let brightness = self.brightness;
+        neopixel::write_colors(&mut self.bus(), addr, colors, PixelFormat::Rgb, brightness)
+    }
+
+    pub fn sync_neopixel(&mut self) -> Result<(), SeesawError<D::I2cError>> {
+        self.require(Modules::Neopixel)?;
+        let addr = self.addr;
+        self.bus()
+            .register_write(addr, neopixel::SHOW, &[])
+            .map(|_| self.driver.delay_us(self.timing.write_delay_us))
+    }
+
+    // Encoder module
+
+    pub fn delta(&mut self) -> Result<i32, SeesawError<D::I2cError>> {
+        self.require(Modules::Encoder)?;
+        let addr = self.addr;
+        self.bus()
+            .read_i32(addr, encoder::DELTA)
+            .map_err(SeesawError::i2c(encoder::DELTA))
+    }
+
+    pub fn position(&mut self) -> Result<i32, SeesawError<D::I2cError>> {
+        self.require(Modules::Encoder)?;
+        let addr = self.addr;
+        self.bus()
+            .read_i32(addr, encoder::POSITION)
+            .map_err(SeesawError::i2c(encoder::POSITION))
+    }
+
+    pub fn set_position(&mut self, pos: i32) -> Result<(), SeesawError<D::I2cError>> {
+        self.require(Modules::Encoder)?;
+        let addr = self.addr;
+        self.bus()
+            .write_i32(addr, encoder::POSITION, pos)
+            .map_err(SeesawError::i2c(encoder::POSITION))
+    }
+}
@@ -0,0 +1,73 @@
+//! Adapters for HALs that only implement the `embedded-hal` 1.0 traits.
+//!
+//! Wrap a 1.0 `I2c` bus in [`I2c`] and a 1.0 `DelayNs` in [`Delay`] and they
+//! can be handed to [`Seesaw`](crate::Seesaw) like any 0.2 implementation.
+//! Bus errors are reported as their [`ErrorKind`], so the error type is the
+//! same regardless of the underlying HAL.
+use embedded_hal::blocking::{delay, i2c};
+use embedded_hal_1::{
+    delay::DelayNs,
+    i2c::{Error as _, I2c as I2c1},
+};
+
+pub use embedded_hal_1::i2c::ErrorKind;
+
+/// Adapts an `embedded-hal` 1.0 I2C bus to the 0.2 blocking I2C traits
+#[derive(Debug)]
+pub struct I2c<T>(pub T);
+
+impl<T: I2c1> I2c<T> {
+    pub fn new(i2c: T) -> Self {
+        Self(i2c)
+    }
+
+    pub fn release(self) -> T {
+        self.0
+    }
+}
+
+impl<T: I2c1> i2c::Write for I2c<T> {
+    type Error = ErrorKind;
+
+    fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.0.write(addr, bytes).map_err(|err| err.kind())
+    }
+}
+
+impl<T: I2c1> i2c::Read for I2c<T> {
+    type Error = ErrorKind;
+
+    fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.0.read(addr, buffer).map_err(|err| err.kind())
+    }
+}
+
+impl<T: I2c1> i2c::WriteRead for I2c<T> {
+    type Error = ErrorKind;
+
+    fn write_read(&mut self, addr: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.0
+            .write_read(addr, bytes, buffer)
+            .map_err(|err| err.kind())
+    }
+}
+
+/// Adapts an `embedded-hal` 1.0 `DelayNs` to the 0.2 `DelayUs<u32>` trait
+#[derive(Debug)]
+pub struct Delay<T>(pub T);
+
+impl<T: DelayNs> Delay<T> {
+    pub fn new(delay: T) -> Self {
+        Self(delay)
+    }
+
+    pub fn release(self) -> T {
+        self.0
+    }
+}
+
+impl<T: DelayNs> delay::DelayUs<u32> for Delay<T> {
+    fn delay_us(&mut self, us: u32) {
+        self.0.delay_us(us)
+    }
+}
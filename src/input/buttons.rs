@@ -0,0 +1,240 @@
+use crate::{driver::Driver, modules::gpio::GpioModule, trace::Clock, SeesawDevice, SeesawError};
+
+/// A debounced change of a button
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ButtonEvent {
+    /// The button on `pin` went down. `at` is when the press was confirmed.
+    Pressed { pin: u8, at: u64 },
+    /// The button on `pin` came back up. `at` is when the release was
+    /// confirmed.
+    Released { pin: u8, at: u64 },
+}
+
+impl ButtonEvent {
+    pub fn pin(&self) -> u8 {
+        match *self {
+            ButtonEvent::Pressed { pin, .. } | ButtonEvent::Released { pin, .. } => pin,
+        }
+    }
+
+    pub fn at(&self) -> u64 {
+        match *self {
+            ButtonEvent::Pressed { at, .. } | ButtonEvent::Released { at, .. } => at,
+        }
+    }
+}
+
+/// Debounces the buttons on a device's pins and reports their presses and
+/// releases.
+///
+/// Buttons are taken to be wired to ground with a pullup, like the ones on
+/// seesaw boards, so a pin reading low is pressed. A pin has to read the same
+/// for `debounce` (in the clock's units) before the change counts.
+///
+/// ```ignore
+/// let mut buttons = ButtonEvents::new(neokeys, 0xF0, || timer.now_ms(), 20);
+/// loop {
+///     for event in buttons.poll()? {
+///         match event {
+///             ButtonEvent::Pressed { pin, .. } => rprintln!("{pin} down"),
+///             ButtonEvent::Released { pin, .. } => rprintln!("{pin} up"),
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ButtonEvents<DEV, C> {
+    device: DEV,
+    mask: u32,
+    clock: C,
+    debounce: u64,
+    /// Debounced state, a set bit being a pressed button
+    pressed: u32,
+    /// The last raw reading
+    raw: u32,
+    /// When each pin's raw reading last changed
+    changed_at: [u64; 32],
+}
+
+impl<D, DEV, C> ButtonEvents<DEV, C>
+where
+    D: Driver,
+    DEV: SeesawDevice<Driver = D> + GpioModule<D>,
+    C: Clock,
+{
+    /// Watch the buttons on the pins set in `mask`, which all start out
+    /// released
+    pub fn new(device: DEV, mask: u32, clock: C, debounce: u64) -> Self {
+        Self {
+            device,
+            mask,
+            clock,
+            debounce,
+            pressed: 0,
+            raw: 0,
+            changed_at: [0; 32],
+        }
+    }
+
+    pub fn device(&mut self) -> &mut DEV {
+        &mut self.device
+    }
+
+    pub fn mask(&self) -> u32 {
+        self.mask
+    }
+
+    /// The debounced state of the buttons, a set bit being a pressed button
+    pub fn pressed(&self) -> u32 {
+        self.pressed
+    }
+
+    pub fn release(self) -> DEV {
+        self.device
+    }
+
+    /// Read the buttons, and return the presses and releases that have
+    /// outlasted the debounce window since the last poll
+    pub fn poll(&mut self) -> Result<Events, SeesawError<D::I2cError>> {
+        let raw = !self.device.digital_read_bulk()? & self.mask;
+        let now = self.clock.now();
+
+        let changed = raw ^ self.raw;
+        for pin in bits(changed) {
+            self.changed_at[pin as usize] = now;
+        }
+        self.raw = raw;
+
+        let settled = bits(raw ^ self.pressed)
+            .filter(|&pin| now.wrapping_sub(self.changed_at[pin as usize]) >= self.debounce)
+            .fold(0, |settled, pin| settled | 1 << pin);
+        self.pressed ^= settled;
+
+        Ok(Events {
+            pressed: settled & self.pressed,
+            released: settled & !self.pressed,
+            at: now,
+        })
+    }
+}
+
+/// The events from one [`ButtonEvents::poll`], releases first and then
+/// presses, each in pin order
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Events {
+    pressed: u32,
+    released: u32,
+    at: u64,
+}
+
+impl Events {
+    /// Bitmask of the pins pressed in this poll
+    pub fn pressed(&self) -> u32 {
+        self.pressed
+    }
+
+    /// Bitmask of the pins released in this poll
+    pub fn released(&self) -> u32 {
+        self.released
+    }
+}
+
+impl Iterator for Events {
+    type Item = ButtonEvent;
+
+    fn next(&mut self) -> Option<ButtonEvent> {
+        let at = self.at;
+        if let Some(pin) = bits(self.released).next() {
+            self.released &= !(1 << pin);
+            Some(ButtonEvent::Released { pin, at })
+        } else if let Some(pin) = bits(self.pressed).next() {
+            self.pressed &= !(1 << pin);
+            Some(ButtonEvent::Pressed { pin, at })
+        } else {
+            None
+        }
+    }
+}
+
+/// The set bits of `mask`, lowest first
+fn bits(mask: u32) -> impl Iterator<Item = u8> {
+    (0..32).filter(move |pin| mask >> pin & 1 == 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ButtonEvent, ButtonEvents};
+    use crate::{
+        devices::NeoKey1x4,
+        mock::{read, MockDriver},
+        SeesawDevice,
+    };
+    use std::cell::Cell;
+
+    const ADDR: u8 = 0x30;
+
+    /// Expect a GPIO read for each of `levels`
+    fn reads(levels: &[u8]) -> MockDriver {
+        let expectations: Vec<_> = levels
+            .iter()
+            .flat_map(|&level| read(ADDR, [0x01, 0x04], &[0, 0, 0, level]))
+            .collect();
+        MockDriver::new(&expectations)
+    }
+
+    #[test]
+    fn press_and_release() {
+        let now = Cell::new(0);
+        let bus = reads(&[0xF0, 0xE0, 0xE0, 0xF0, 0xF0]);
+        let mut buttons = ButtonEvents::new(NeoKey1x4::new(ADDR, bus), 0xF0, || now.get(), 10);
+        let mut poll_at = |t| {
+            now.set(t);
+            buttons.poll().unwrap().collect::<Vec<_>>()
+        };
+
+        assert_eq!(poll_at(0), []);
+        assert_eq!(poll_at(5), []);
+        assert_eq!(poll_at(15), [ButtonEvent::Pressed { pin: 4, at: 15 }]);
+        assert_eq!(poll_at(20), []);
+        assert_eq!(poll_at(30), [ButtonEvent::Released { pin: 4, at: 30 }]);
+        buttons.device().driver().done();
+    }
+
+    #[test]
+    fn bounces_are_ignored() {
+        let now = Cell::new(0);
+        let bus = reads(&[0xE0, 0xF0, 0xE0, 0xE0, 0xE0]);
+        let mut buttons = ButtonEvents::new(NeoKey1x4::new(ADDR, bus), 0xF0, || now.get(), 10);
+        let mut poll_at = |t| {
+            now.set(t);
+            buttons.poll().unwrap().collect::<Vec<_>>()
+        };
+
+        assert_eq!(poll_at(0), []);
+        assert_eq!(poll_at(3), []);
+        assert_eq!(poll_at(6), []);
+        assert_eq!(poll_at(14), []);
+        assert_eq!(poll_at(16), [ButtonEvent::Pressed { pin: 4, at: 16 }]);
+        assert_eq!(buttons.pressed(), 0x10);
+        buttons.device().driver().done();
+    }
+
+    #[test]
+    fn events_in_pin_order() {
+        let now = Cell::new(0);
+        let bus = reads(&[0x30, 0xC0]);
+        let mut buttons = ButtonEvents::new(NeoKey1x4::new(ADDR, bus), 0xF0, || now.get(), 0);
+        let events: Vec<_> = buttons.poll().unwrap().collect();
+        assert_eq!(
+            events,
+            [
+                ButtonEvent::Pressed { pin: 6, at: 0 },
+                ButtonEvent::Pressed { pin: 7, at: 0 }
+            ]
+        );
+        let events: Vec<_> = buttons.poll().unwrap().map(|event| event.pin()).collect();
+        assert_eq!(events, [6, 7, 4, 5]);
+        buttons.device().driver().done();
+    }
+}
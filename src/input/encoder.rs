@@ -0,0 +1,313 @@
+use crate::{
+    driver::Driver, modules::encoder::EncoderModule, trace::Clock, SeesawDevice, SeesawError,
+};
+
+/// The values an [`Encoder`] can take. An encoder swaps a `min` and `max`
+/// given the wrong way round.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Range {
+    /// Any `i32`, stopping at either end
+    Unbounded,
+    /// Stops at `min` and `max`
+    Bounded { min: i32, max: i32 },
+    /// Goes from `max` round to `min`, and back
+    Wrapping { min: i32, max: i32 },
+}
+
+impl Range {
+    /// The same range with `min` no greater than `max`
+    pub const fn normalized(self) -> Self {
+        match self {
+            Range::Bounded { min, max } if min > max => Range::Bounded { min: max, max: min },
+            Range::Wrapping { min, max } if min > max => Range::Wrapping { min: max, max: min },
+            range => range,
+        }
+    }
+
+    fn step(self, value: i32, steps: i32) -> i32 {
+        match self {
+            Range::Unbounded => value.saturating_add(steps),
+            Range::Bounded { min, max } => value.saturating_add(steps).clamp(min, max),
+            Range::Wrapping { min, max } => {
+                let len = i64::from(max) - i64::from(min) + 1;
+                let offset = i64::from(value) - i64::from(min) + i64::from(steps);
+                (i64::from(min) + offset.rem_euclid(len)) as i32
+            }
+        }
+    }
+
+    fn contain(self, value: i32) -> i32 {
+        self.step(value, 0)
+    }
+}
+
+/// How an [`Encoder`] turns counts into steps
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct EncoderConfig {
+    /// How many counts the encoder makes per detent. Counts short of a whole
+    /// detent are kept until the next poll.
+    pub counts_per_detent: i32,
+    /// `(under, multiplier)` pairs, fastest first: when the previous detent
+    /// was less than `under` ago in the clock's units, each detent counts as
+    /// `multiplier` steps. Slower detents count as one step.
+    pub acceleration: &'static [(u64, i32)],
+    pub range: Range,
+}
+
+impl EncoderConfig {
+    pub const DEFAULT: Self = Self {
+        counts_per_detent: 1,
+        acceleration: &[],
+        range: Range::Unbounded,
+    };
+}
+
+impl Default for EncoderConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// What happened to an [`Encoder`] since the last poll
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Turn {
+    /// Whole detents turned, clockwise being positive
+    pub detents: i32,
+    /// The detents after acceleration
+    pub steps: i32,
+    /// The value after the steps, kept inside the range
+    pub value: i32,
+    /// Whether the encoder's button is held down
+    pub pressed: bool,
+}
+
+impl Turn {
+    /// The encoder was turned with its button held down
+    pub fn is_push_turn(&self) -> bool {
+        self.pressed && self.detents != 0
+    }
+}
+
+/// Turns the raw counts of an encoder into a value, with detents,
+/// acceleration and a range.
+///
+/// Counts are read with [`EncoderModule::delta`], which the seesaw resets as
+/// it's read, so no counts are lost between polls.
+///
+/// ```ignore
+/// let config = EncoderConfig {
+///     acceleration: &[(20, 5), (60, 2)],
+///     range: Range::Bounded { min: 0, max: 100 },
+///     ..EncoderConfig::DEFAULT
+/// };
+/// let mut volume = Encoder::new(encoder, config, || timer.now_ms());
+/// loop {
+///     let turn = volume.poll()?;
+///     if turn.is_push_turn() {
+///         // Scrub instead
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Encoder<DEV, C> {
+    device: DEV,
+    config: EncoderConfig,
+    clock: C,
+    value: i32,
+    /// Counts short of a whole detent
+    counts: i32,
+    last_detent_at: Option<u64>,
+}
+
+impl<D, DEV, C> Encoder<DEV, C>
+where
+    D: Driver,
+    DEV: SeesawDevice<Driver = D> + EncoderModule<D>,
+    C: Clock,
+{
+    /// Start at 0, or the end of the range nearest it
+    pub fn new(device: DEV, mut config: EncoderConfig, clock: C) -> Self {
+        config.range = config.range.normalized();
+        Self {
+            device,
+            config,
+            clock,
+            value: config.range.contain(0),
+            counts: 0,
+            last_detent_at: None,
+        }
+    }
+
+    pub fn device(&mut self) -> &mut DEV {
+        &mut self.device
+    }
+
+    pub fn config(&self) -> EncoderConfig {
+        self.config
+    }
+
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    /// Set the value, keeping it inside the range
+    pub fn set_value(&mut self, value: i32) {
+        self.value = self.config.range.contain(value);
+    }
+
+    pub fn release(self) -> DEV {
+        self.device
+    }
+
+    /// Read the counts and button since the last poll, and step the value
+    pub fn poll(&mut self) -> Result<Turn, SeesawError<D::I2cError>> {
+        let delta = self.device.delta()?;
+        let pressed = self.device.button()?;
+        let now = self.clock.now();
+
+        let counts = self.counts.saturating_add(delta);
+        let per_detent = self.config.counts_per_detent.max(1);
+        let detents = counts / per_detent;
+        self.counts = counts % per_detent;
+
+        let mut steps = detents;
+        if detents != 0 {
+            let since = self.last_detent_at.map(|at| now.saturating_sub(at));
+            let multiplier = self
+                .config
+                .acceleration
+                .iter()
+                .find(|&&(under, _)| since.is_some_and(|since| since < under))
+                .map_or(1, |&(_, multiplier)| multiplier);
+            steps = detents.saturating_mul(multiplier);
+            self.last_detent_at = Some(now);
+            self.value = self.config.range.step(self.value, steps);
+        }
+
+        Ok(Turn {
+            detents,
+            steps,
+            value: self.value,
+            pressed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Encoder, EncoderConfig, Range};
+    use crate::{
+        devices::RotaryEncoder,
+        mock::{read, MockDriver},
+        SeesawDevice,
+    };
+    use std::cell::Cell;
+
+    const ADDR: u8 = 0x36;
+
+    /// Expect a poll reading `delta`, with the button up or down
+    fn polls(turns: &[(i32, bool)]) -> MockDriver {
+        let expectations: Vec<_> = turns
+            .iter()
+            .flat_map(|&(delta, pressed)| {
+                let levels = [u8::from(!pressed), 0, 0, 0];
+                [
+                    read(ADDR, [0x11, 0x40], &delta.to_be_bytes()),
+                    read(ADDR, [0x01, 0x04], &levels),
+                ]
+                .concat()
+            })
+            .collect();
+        MockDriver::new(&expectations)
+    }
+
+    #[test]
+    fn detents_keep_leftover_counts() {
+        let config = EncoderConfig {
+            counts_per_detent: 4,
+            ..EncoderConfig::DEFAULT
+        };
+        let bus = polls(&[(3, false), (6, false), (-2, false)]);
+        let mut encoder = Encoder::new(RotaryEncoder::new(ADDR, bus), config, || 0);
+        assert_eq!(encoder.poll().unwrap().detents, 0);
+        assert_eq!(encoder.poll().unwrap().detents, 2);
+        assert_eq!(encoder.poll().unwrap().detents, 0);
+        assert_eq!(encoder.value(), 2);
+        encoder.device().driver().done();
+    }
+
+    #[test]
+    fn fast_turns_accelerate() {
+        let now = Cell::new(0);
+        let config = EncoderConfig {
+            acceleration: &[(10, 5), (50, 2)],
+            ..EncoderConfig::DEFAULT
+        };
+        let bus = polls(&[(1, false), (1, false), (1, false), (1, false)]);
+        let mut encoder = Encoder::new(RotaryEncoder::new(ADDR, bus), config, || now.get());
+        let mut steps_at = |t| {
+            now.set(t);
+            encoder.poll().unwrap().steps
+        };
+        assert_eq!(steps_at(0), 1);
+        assert_eq!(steps_at(100), 1);
+        assert_eq!(steps_at(130), 2);
+        assert_eq!(steps_at(135), 5);
+        assert_eq!(encoder.value(), 9);
+        encoder.device().driver().done();
+    }
+
+    #[test]
+    fn ranges() {
+        let bounded = Range::Bounded { min: 0, max: 100 };
+        assert_eq!(bounded.step(95, 10), 100);
+        assert_eq!(bounded.step(5, -10), 0);
+        let wrapping = Range::Wrapping { min: 1, max: 12 };
+        assert_eq!(wrapping.step(11, 3), 2);
+        assert_eq!(wrapping.step(1, -1), 12);
+        assert_eq!(wrapping.step(1, -25), 12);
+        assert_eq!(Range::Unbounded.step(i32::MAX, 1), i32::MAX);
+    }
+
+    #[test]
+    fn inverted_and_single_value_ranges() {
+        let inverted = EncoderConfig {
+            range: Range::Bounded { min: 10, max: 0 },
+            ..EncoderConfig::DEFAULT
+        };
+        let bus = polls(&[(15, false), (-3, false)]);
+        let mut encoder = Encoder::new(RotaryEncoder::new(ADDR, bus), inverted, || 0);
+        assert_eq!(encoder.config().range, Range::Bounded { min: 0, max: 10 });
+        assert_eq!(encoder.poll().unwrap().value, 10);
+        assert_eq!(encoder.poll().unwrap().value, 7);
+        encoder.device().driver().done();
+
+        let wrapping = EncoderConfig {
+            range: Range::Wrapping { min: 0, max: -1 },
+            ..EncoderConfig::DEFAULT
+        };
+        let bus = polls(&[(1, false), (1, false)]);
+        let mut encoder = Encoder::new(RotaryEncoder::new(ADDR, bus), wrapping, || 0);
+        assert_eq!(encoder.poll().unwrap().value, -1);
+        assert_eq!(encoder.poll().unwrap().value, 0);
+        encoder.device().driver().done();
+
+        let single = Range::Wrapping { min: 5, max: 5 };
+        assert_eq!(single.step(5, 7), 5);
+        assert_eq!(single.step(5, -3), 5);
+        assert_eq!(Range::Bounded { min: 5, max: 5 }.step(0, 1), 5);
+    }
+
+    #[test]
+    fn push_and_turn() {
+        let bus = polls(&[(1, true), (0, true), (-1, false)]);
+        let mut encoder = Encoder::new(RotaryEncoder::new(ADDR, bus), EncoderConfig::DEFAULT, || 0);
+        assert!(encoder.poll().unwrap().is_push_turn());
+        let held = encoder.poll().unwrap();
+        assert!(held.pressed && !held.is_push_turn());
+        assert!(!encoder.poll().unwrap().is_push_turn());
+        encoder.device().driver().done();
+    }
+}
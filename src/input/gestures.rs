@@ -0,0 +1,373 @@
+use super::ButtonEvent;
+
+/// How long the parts of each gesture take, in the clock's units
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GestureConfig {
+    /// How soon after a click the second press has to come for a double
+    /// click. With 0, clicks are reported on release without waiting.
+    pub double_click: u64,
+    /// How long a button has to be held for a long press
+    pub long_press: u64,
+    /// How long a button has to be held before it starts repeating
+    pub repeat_after: u64,
+    /// How often a held button repeats. With 0, buttons don't repeat.
+    pub repeat_every: u64,
+    /// How close together the presses of a chord have to be
+    pub chord: u64,
+}
+
+impl GestureConfig {
+    /// Thresholds for a clock counting milliseconds
+    pub const MILLIS: Self = Self {
+        double_click: 300,
+        long_press: 600,
+        repeat_after: 600,
+        repeat_every: 100,
+        chord: 50,
+    };
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self::MILLIS
+    }
+}
+
+/// A gesture recognized by [`Gestures`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Gesture {
+    /// A press and release, with no second press after it
+    Click { pin: u8 },
+    /// Two clicks in a row
+    DoubleClick { pin: u8 },
+    /// A press held for [`GestureConfig::long_press`]. The release that
+    /// follows isn't a click.
+    LongPress { pin: u8 },
+    /// A held button repeating
+    Repeat { pin: u8 },
+    /// Several buttons pressed together. The buttons in a chord don't make
+    /// any other gestures until they're released.
+    Chord { pins: u32 },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Key {
+    Up,
+    Down {
+        since: u64,
+        /// The second press of a double click
+        second: bool,
+        long: bool,
+        repeated: bool,
+        next_repeat: u64,
+    },
+    /// Released after a click, waiting for a second press
+    Clicked {
+        at: u64,
+    },
+    /// Part of a chord, until it's released
+    Chorded,
+}
+
+/// Recognizes gestures from the [`ButtonEvent`]s of up to 32 buttons, and
+/// queues up to `N` of them for the UI loop to drain. When the queue is full
+/// the oldest gesture is dropped.
+///
+/// ```ignore
+/// let mut keys = ButtonEvents::new(neokeys, 0xF0, || timer.now_ms(), 20);
+/// let mut gestures = Gestures::<8>::new(GestureConfig::MILLIS);
+/// loop {
+///     gestures.update(timer.now_ms(), keys.poll()?);
+///     for gesture in gestures.drain() {
+///         rprintln!("{:?}", gesture);
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Gestures<const N: usize> {
+    config: GestureConfig,
+    keys: [Key; 32],
+    /// Buttons pressed within the chord window, and when the first was
+    chord: u32,
+    chord_since: u64,
+    queue: [Option<Gesture>; N],
+    head: usize,
+    len: usize,
+    dropped: usize,
+}
+
+impl<const N: usize> Gestures<N> {
+    pub fn new(config: GestureConfig) -> Self {
+        Self {
+            config,
+            keys: [Key::Up; 32],
+            chord: 0,
+            chord_since: 0,
+            queue: [None; N],
+            head: 0,
+            len: 0,
+            dropped: 0,
+        }
+    }
+
+    pub fn config(&self) -> GestureConfig {
+        self.config
+    }
+
+    /// Take in the button events since the last update, then recognize the
+    /// gestures that time alone completes, as of `now`
+    pub fn update(&mut self, now: u64, events: impl IntoIterator<Item = ButtonEvent>) {
+        for event in events {
+            match event {
+                ButtonEvent::Pressed { pin, at } => self.press(pin, at),
+                ButtonEvent::Released { pin, at } => self.release(pin, at),
+            }
+        }
+        self.tick(now);
+    }
+
+    /// The next queued gesture, oldest first
+    pub fn pop(&mut self) -> Option<Gesture> {
+        if self.len == 0 {
+            return None;
+        }
+        let gesture = self.queue[self.head].take();
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        gesture
+    }
+
+    /// Pop every queued gesture
+    pub fn drain(&mut self) -> impl Iterator<Item = Gesture> + '_ {
+        core::iter::from_fn(|| self.pop())
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// How many gestures were dropped because the queue was full
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    fn press(&mut self, pin: u8, at: u64) {
+        let Some(key) = self.keys.get_mut(pin as usize) else {
+            return;
+        };
+        let second = matches!(*key, Key::Clicked { at: clicked }
+            if at.saturating_sub(clicked) <= self.config.double_click);
+        *key = Key::Down {
+            since: at,
+            second,
+            long: false,
+            repeated: false,
+            next_repeat: at + self.config.repeat_after,
+        };
+
+        if self.chord == 0 || at.saturating_sub(self.chord_since) > self.config.chord {
+            self.chord = 0;
+            self.chord_since = at;
+        }
+        self.chord |= 1 << pin;
+    }
+
+    fn release(&mut self, pin: u8, at: u64) {
+        let Some(key) = self.keys.get_mut(pin as usize) else {
+            return;
+        };
+        self.chord &= !(1 << pin);
+        match *key {
+            Key::Down { long: true, .. }
+            | Key::Down { repeated: true, .. }
+            | Key::Chorded
+            | Key::Up
+            | Key::Clicked { .. } => *key = Key::Up,
+            Key::Down { second: true, .. } => {
+                *key = Key::Up;
+                self.push(Gesture::DoubleClick { pin });
+            }
+            Key::Down { .. } if self.config.double_click > 0 => {
+                *key = Key::Clicked { at };
+            }
+            Key::Down { .. } => {
+                *key = Key::Up;
+                self.push(Gesture::Click { pin });
+            }
+        }
+    }
+
+    fn tick(&mut self, now: u64) {
+        if self.chord.count_ones() > 1 && now.saturating_sub(self.chord_since) >= self.config.chord
+        {
+            let pins = self.chord;
+            for pin in 0..32 {
+                if pins >> pin & 1 == 1 {
+                    self.keys[pin] = Key::Chorded;
+                }
+            }
+            self.chord = 0;
+            self.push(Gesture::Chord { pins });
+        }
+
+        let config = self.config;
+        for pin in 0..32u8 {
+            match self.keys[pin as usize] {
+                Key::Clicked { at } if now.saturating_sub(at) > config.double_click => {
+                    self.keys[pin as usize] = Key::Up;
+                    self.push(Gesture::Click { pin });
+                }
+                Key::Down {
+                    since,
+                    second,
+                    long,
+                    repeated,
+                    mut next_repeat,
+                } => {
+                    let long_press = !long && now.saturating_sub(since) >= config.long_press;
+                    let mut repeats = 0;
+                    while config.repeat_every > 0 && next_repeat <= now {
+                        next_repeat += config.repeat_every;
+                        repeats += 1;
+                    }
+                    self.keys[pin as usize] = Key::Down {
+                        since,
+                        second,
+                        long: long || long_press,
+                        repeated: repeated || repeats > 0,
+                        next_repeat,
+                    };
+                    if long_press {
+                        self.push(Gesture::LongPress { pin });
+                    }
+                    (0..repeats).for_each(|_| self.push(Gesture::Repeat { pin }));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn push(&mut self, gesture: Gesture) {
+        if N == 0 {
+            self.dropped += 1;
+            return;
+        }
+        if self.len == N {
+            self.head = (self.head + 1) % N;
+            self.len -= 1;
+            self.dropped += 1;
+        }
+        self.queue[(self.head + self.len) % N] = Some(gesture);
+        self.len += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Gesture, GestureConfig, Gestures};
+    use crate::input::ButtonEvent;
+
+    const CONFIG: GestureConfig = GestureConfig {
+        double_click: 30,
+        long_press: 100,
+        repeat_after: 150,
+        repeat_every: 20,
+        chord: 10,
+    };
+
+    fn press(pin: u8, at: u64) -> Option<ButtonEvent> {
+        Some(ButtonEvent::Pressed { pin, at })
+    }
+
+    fn release(pin: u8, at: u64) -> Option<ButtonEvent> {
+        Some(ButtonEvent::Released { pin, at })
+    }
+
+    #[test]
+    fn click_waits_out_double_click_window() {
+        let mut gestures = Gestures::<4>::new(CONFIG);
+        gestures.update(0, press(4, 0));
+        gestures.update(20, release(4, 20));
+        gestures.update(50, None);
+        assert_eq!(gestures.pop(), None);
+        gestures.update(51, None);
+        assert_eq!(
+            gestures.drain().collect::<Vec<_>>(),
+            [Gesture::Click { pin: 4 }]
+        );
+    }
+
+    #[test]
+    fn double_click() {
+        let mut gestures = Gestures::<4>::new(CONFIG);
+        gestures.update(0, press(4, 0));
+        gestures.update(10, release(4, 10));
+        gestures.update(20, press(4, 20));
+        gestures.update(25, release(4, 25));
+        gestures.update(100, None);
+        assert_eq!(
+            gestures.drain().collect::<Vec<_>>(),
+            [Gesture::DoubleClick { pin: 4 }]
+        );
+    }
+
+    #[test]
+    fn long_press_then_repeat() {
+        let mut gestures = Gestures::<8>::new(CONFIG);
+        gestures.update(0, press(5, 0));
+        gestures.update(100, None);
+        gestures.update(195, None);
+        gestures.update(200, release(5, 200));
+        gestures.update(300, None);
+        assert_eq!(
+            gestures.drain().collect::<Vec<_>>(),
+            [
+                Gesture::LongPress { pin: 5 },
+                Gesture::Repeat { pin: 5 },
+                Gesture::Repeat { pin: 5 },
+                Gesture::Repeat { pin: 5 },
+            ]
+        );
+    }
+
+    #[test]
+    fn chord() {
+        let mut gestures = Gestures::<4>::new(CONFIG);
+        gestures.update(0, press(4, 0));
+        gestures.update(5, [press(6, 5), press(7, 5)].into_iter().flatten());
+        gestures.update(10, None);
+        gestures.update(
+            20,
+            [4, 6, 7].map(|pin| release(pin, 20)).into_iter().flatten(),
+        );
+        gestures.update(100, None);
+        assert_eq!(
+            gestures.drain().collect::<Vec<_>>(),
+            [Gesture::Chord { pins: 0b1101_0000 }]
+        );
+    }
+
+    #[test]
+    fn full_queue_drops_oldest() {
+        let config = GestureConfig {
+            double_click: 0,
+            ..CONFIG
+        };
+        let mut gestures = Gestures::<2>::new(config);
+        for pin in 0..3 {
+            gestures.update(pin as u64 * 100, press(pin, pin as u64 * 100));
+            gestures.update(pin as u64 * 100 + 1, release(pin, pin as u64 * 100 + 1));
+        }
+        assert_eq!(gestures.dropped(), 1);
+        assert_eq!(
+            gestures.drain().collect::<Vec<_>>(),
+            [Gesture::Click { pin: 1 }, Gesture::Click { pin: 2 }]
+        );
+    }
+}
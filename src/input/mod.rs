@@ -0,0 +1,12 @@
+//! Turning raw reads of buttons and encoders into events.
+//!
+//! The timing in these helpers comes from a user-supplied
+//! [`Clock`](crate::trace::Clock), in whatever unit it counts, so windows and
+//! thresholds are given in that unit too.
+mod buttons;
+mod encoder;
+mod gestures;
+
+pub use buttons::*;
+pub use encoder::*;
+pub use gestures::*;
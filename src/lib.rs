@@ -1,29 +1,88 @@
-#![no_std]
-#![allow(const_evaluatable_unchecked, incomplete_features)]
-#![feature(array_try_map, generic_const_exprs)]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+#![cfg_attr(feature = "async", allow(async_fn_in_trait))]
 // TODO improve the organization of the exports/visibility
 use embedded_hal::blocking::delay;
+use modules::gpio::PinMode;
+pub mod animations;
 pub mod bus;
+#[cfg(feature = "cli")]
+pub mod cli;
+mod color;
 mod common;
 pub mod devices;
 mod driver;
+pub mod dynamic;
+#[cfg(feature = "embedded-hal-1")]
+pub mod eh1;
+pub mod input;
 mod macros;
+#[cfg(test)]
+mod mock;
 pub mod modules;
+mod pinout;
+pub mod pins;
+pub mod retry;
+#[cfg(feature = "sim")]
+pub mod sim;
+mod timing;
+pub mod trace;
+pub use color::*;
 pub use common::*;
 pub use devices::*;
 pub use driver::*;
+pub use dynamic::*;
+pub use pinout::*;
+pub use timing::*;
 
 pub mod prelude {
     pub use super::{
         devices::*,
         driver::DriverExt,
-        modules::{adc::*, encoder::*, gpio::*, neopixel::*, status::*, timer::*},
-        SeesawDevice, SeesawDeviceInit,
+        modules::{
+            adc::AdcModule,
+            encoder::EncoderModule,
+            gpio::{GpioModule, InterruptMode, PinMode},
+            neopixel::{NeopixelModule, NeopixelSpeed},
+            status::{DeviceCapabilities, ProductDateCode, StatusModule},
+            timer::TimerModule,
+            touch::TouchModule,
+        },
+        Hsv, Rgb, Rgbw, SeesawDevice, SeesawDeviceInit,
     };
+
+    /// The async module traits. These are kept apart from the blocking ones
+    /// because their method names are the same.
+    #[cfg(feature = "async")]
+    pub mod asynch {
+        pub use crate::{
+            devices::*,
+            driver::DriverExtAsync,
+            modules::{
+                adc::AdcModuleAsync, encoder::EncoderModuleAsync, gpio::GpioModuleAsync,
+                neopixel::NeopixelModuleAsync, status::StatusModuleAsync, timer::TimerModuleAsync,
+                touch::TouchModuleAsync,
+            },
+            SeesawDeviceAsync, SeesawDeviceInitAsync,
+        };
+    }
 }
 
 pub type SeesawSingleThread<BUS> = Seesaw<shared_bus::NullMutex<BUS>>;
 
+/// Shares the bus between interrupt handlers or RTIC tasks on a single-core
+/// Cortex-M
+#[cfg(feature = "cortex-m")]
+pub type SeesawCortexM<BUS> = Seesaw<shared_bus::CortexMMutex<BUS>>;
+
+/// Shares the bus between std threads
+#[cfg(feature = "std")]
+pub type SeesawStd<BUS> = Seesaw<std::sync::Mutex<BUS>>;
+
+/// Shares the bus between any contexts that the target's `critical-section`
+/// implementation covers
+#[cfg(feature = "critical-section")]
+pub type SeesawCriticalSection<BUS> = Seesaw<bus::CriticalSectionMutex<BUS>>;
+
 pub struct Seesaw<M> {
     mutex: M,
 }
@@ -45,14 +104,131 @@ where
     }
 }
 
+#[cfg(feature = "embedded-hal-1")]
+impl<DELAY, I2C, M> Seesaw<M>
+where
+    DELAY: embedded_hal_1::delay::DelayNs,
+    I2C: embedded_hal_1::i2c::I2c,
+    M: shared_bus::BusMutex<Bus = bus::Bus<eh1::Delay<DELAY>, eh1::I2c<I2C>>>,
+{
+    /// Create a `Seesaw` from a delay and I2C bus that implement the
+    /// `embedded-hal` 1.0 traits
+    pub fn new_eh1(delay: DELAY, i2c: I2C) -> Self {
+        Seesaw {
+            mutex: M::create(bus::Bus(eh1::Delay(delay), eh1::I2c(i2c))),
+        }
+    }
+}
+
+/// A `Seesaw` on a Linux I2C character device, e.g. on a Raspberry Pi
+#[cfg(feature = "linux")]
+impl<M> Seesaw<M>
+where
+    M: shared_bus::BusMutex<Bus = bus::Bus<linux_embedded_hal::Delay, linux_embedded_hal::I2cdev>>,
+{
+    /// Open the I2C bus at `path`, e.g. `/dev/i2c-1`
+    pub fn new_linux(
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<Self, linux_embedded_hal::i2cdev::linux::LinuxI2CError> {
+        linux_embedded_hal::I2cdev::new(path).map(Self::new_i2cdev)
+    }
+
+    /// Use an already opened I2C bus, with a `std::thread::sleep` delay
+    pub fn new_i2cdev(i2c: linux_embedded_hal::I2cdev) -> Self {
+        Self::new(linux_embedded_hal::Delay, i2c)
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum SeesawError<E> {
-    /// I2C bus error
-    I2c(E),
+    /// I2C bus error while reading or writing `reg`
+    I2c { reg: Reg, source: E },
     /// Occurs when an invalid hardware ID is read
     InvalidHardwareId(u8),
+    /// Occurs when a device is asked to use a module it doesn't have
+    ModuleNotSupported(Modules),
+    /// The pin can't be used with the module, e.g. an ADC read on a pin with
+    /// no ADC channel
+    InvalidPin { module: Modules, pin: u8 },
+    /// The device doesn't support the pin mode
+    UnsupportedMode(PinMode),
+    /// The device at the address reports a different product than expected
+    WrongProductId { expected: u16, found: u16 },
+    /// A NeoPixel index past the end of the strip
+    PixelOutOfRange { index: u16, len: u16 },
+    /// A register write of `len` bytes, more than [`MAX_WRITE_LEN`]
+    WriteTooLong { reg: Reg, len: usize },
+    /// A module's FIFO filled up and events were lost.
+    ///
+    /// Reserved for FIFO-backed modules like the keypad, which this crate
+    /// doesn't drive yet. None of the current modules read from a FIFO (the
+    /// encoder's delta is a plain counter), so nothing returns this variant
+    /// today.
+    FifoOverflow(Modules),
+}
+
+impl<E> SeesawError<E> {
+    /// Wrap a bus error that occurred while accessing `reg`, for use with
+    /// `map_err`
+    pub fn i2c(reg: &Reg) -> impl FnOnce(E) -> Self {
+        let reg = *reg;
+        move |source| SeesawError::I2c { reg, source }
+    }
+
+    /// The module involved in the error, if there is one
+    pub fn module(&self) -> Option<Modules> {
+        match self {
+            SeesawError::I2c { reg, .. } | SeesawError::WriteTooLong { reg, .. } => {
+                Modules::try_from(reg[0]).ok()
+            }
+            SeesawError::ModuleNotSupported(module)
+            | SeesawError::InvalidPin { module, .. }
+            | SeesawError::FifoOverflow(module) => Some(*module),
+            SeesawError::UnsupportedMode(_) => Some(Modules::Gpio),
+            SeesawError::PixelOutOfRange { .. } => Some(Modules::Neopixel),
+            SeesawError::InvalidHardwareId(_) | SeesawError::WrongProductId { .. } => {
+                Some(Modules::Status)
+            }
+        }
+    }
+}
+
+impl<E: core::fmt::Debug> core::fmt::Display for SeesawError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SeesawError::I2c { reg, source } => match Modules::try_from(reg[0]) {
+                Ok(module) => write!(
+                    f,
+                    "I2C bus error on {module:?} register {:#04x}: {source:?}",
+                    reg[1]
+                ),
+                Err(_) => write!(f, "I2C bus error on register {reg:02x?}: {source:?}"),
+            },
+            SeesawError::InvalidHardwareId(id) => write!(f, "invalid hardware ID {id:#04x}"),
+            SeesawError::ModuleNotSupported(module) => {
+                write!(f, "device doesn't have the {module:?} module")
+            }
+            SeesawError::InvalidPin { module, pin } => {
+                write!(f, "pin {pin} can't be used with the {module:?} module")
+            }
+            SeesawError::UnsupportedMode(mode) => write!(f, "pin mode {mode:?} isn't supported"),
+            SeesawError::WrongProductId { expected, found } => {
+                write!(f, "expected product {expected}, found {found}")
+            }
+            SeesawError::PixelOutOfRange { index, len } => {
+                write!(f, "pixel {index} is out of range for {len} pixels")
+            }
+            SeesawError::WriteTooLong { reg, len } => {
+                write!(f, "{len} bytes is too long to write to register {reg:02x?}")
+            }
+            SeesawError::FifoOverflow(module) => write!(f, "{module:?} FIFO overflowed"),
+        }
+    }
 }
 
+impl<E: core::fmt::Debug> core::error::Error for SeesawError<E> {}
+
 pub trait SeesawDevice {
     type Error;
     type Driver: Driver;
@@ -65,9 +241,22 @@ pub trait SeesawDevice {
 
     fn driver(&mut self) -> &mut Self::Driver;
 
+    /// The delays used when talking to the device
+    fn timing(&self) -> &Timing;
+
+    /// The driver, with register reads and writes paced by [`Self::timing`]
+    fn bus(&mut self) -> TimedDriver<'_, Self::Driver> {
+        let timing = *self.timing();
+        TimedDriver::new(self.driver(), &timing)
+    }
+
     fn new(addr: u8, driver: Self::Driver) -> Self;
 
     fn new_with_default_addr(driver: Self::Driver) -> Self;
+
+    /// Create the device with a [`Timing`] profile other than the default for
+    /// its [`HardwareId`]
+    fn new_with_timing(addr: u8, driver: Self::Driver, timing: Timing) -> Self;
 }
 
 /// At startup, Seesaw devices typically have a unique set of initialization
@@ -82,3 +271,45 @@ where
 {
     fn init(self) -> Result<Self, Self::Error>;
 }
+
+/// Async counterpart of [`SeesawDevice`], implemented by every device for
+/// drivers that implement [`DriverAsync`].
+#[cfg(feature = "async")]
+pub trait SeesawDeviceAsync {
+    type Error;
+    type Driver: DriverAsync;
+
+    const DEFAULT_ADDR: u8;
+    const HARDWARE_ID: HardwareId;
+    const PRODUCT_ID: u16;
+
+    fn addr(&self) -> u8;
+
+    fn driver(&mut self) -> &mut Self::Driver;
+
+    /// The delays used when talking to the device
+    fn timing(&self) -> &Timing;
+
+    /// The driver, with register reads and writes paced by [`Self::timing`]
+    fn bus(&mut self) -> TimedDriver<'_, Self::Driver> {
+        let timing = *self.timing();
+        TimedDriver::new(self.driver(), &timing)
+    }
+
+    fn new(addr: u8, driver: Self::Driver) -> Self;
+
+    fn new_with_default_addr(driver: Self::Driver) -> Self;
+
+    /// Create the device with a [`Timing`] profile other than the default for
+    /// its [`HardwareId`]
+    fn new_with_timing(addr: u8, driver: Self::Driver, timing: Timing) -> Self;
+}
+
+/// Async counterpart of [`SeesawDeviceInit`]
+#[cfg(feature = "async")]
+pub trait SeesawDeviceInitAsync<D: DriverAsync>: SeesawDeviceAsync<Driver = D>
+where
+    Self: Sized,
+{
+    async fn init(self) -> Result<Self, Self::Error>;
+}
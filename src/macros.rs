@@ -14,11 +14,14 @@ macro_rules! seesaw_device {
         ]
          $(,)?
     ) => {
-        $(#[$attr])*
-        ///
-        #[doc=core::concat!("[Adafruit Product Page](https://www.adafruit.com/product/", core::stringify!($product_id),")")]
-        #[derive(Debug)]
-        pub struct $name<D>(u8, D);
+        device_struct! {
+            $(#[$attr])*
+            ///
+            #[doc=core::concat!("[Adafruit Product Page](https://www.adafruit.com/product/", core::stringify!($product_id),")")]
+            #[derive(Debug)]
+            $name;
+            $($module_name)*
+        }
 
         impl $name<()> {
             pub const fn default_addr() -> u8 {
@@ -47,26 +50,75 @@ macro_rules! seesaw_device {
                 &mut self.1
             }
 
+            fn timing(&self) -> &$crate::Timing {
+                &self.2
+            }
+
             fn new(addr: u8, driver: D) -> Self {
-                Self(addr, driver)
+                Self::new_with_timing(addr, driver, $crate::Timing::for_hardware($hardware_id))
             }
 
             fn new_with_default_addr(driver: D) -> Self {
-                Self(Self::DEFAULT_ADDR, driver)
+                Self::new(Self::DEFAULT_ADDR, driver)
             }
+
+            fn new_with_timing(addr: u8, driver: D, timing: $crate::Timing) -> Self {
+                Self(addr, driver, timing, core::default::Default::default())
+            }
+        }
+
+        impl_device_async! {
+            name: $name,
+            hardware_id: $hardware_id,
+            product_id: $product_id,
+            default_addr: $default_addr
         }
 
         $(
             impl_device_module! { $name, $module_name $({$($const_name: $const_value),*})* }
+            impl_device_module_async! { $name, $module_name $({$($const_name: $const_value),*})* }
         )*
     };
 }
 
+/// The device struct, holding a `NeopixelState` if the device has a NeoPixel
+/// module, or `()` so that devices without one carry nothing
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! device_struct {
+    ($(#[$attr:meta])* $name:ident;) => {
+        $(#[$attr])*
+        pub struct $name<D>(u8, D, $crate::Timing, ());
+    };
+    ($(#[$attr:meta])* $name:ident; NeopixelModule $($rest:ident)*) => {
+        $(#[$attr])*
+        pub struct $name<D>(u8, D, $crate::Timing, $crate::modules::neopixel::NeopixelState);
+    };
+    ($(#[$attr:meta])* $name:ident; $module:ident $($rest:ident)*) => {
+        device_struct! { $(#[$attr])* $name; $($rest)* }
+    };
+}
+
 #[doc(hidden)]
 #[macro_export(local_inner_macros)]
 macro_rules! impl_device_module {
     ($device:ident, AdcModule $({})?) => {
         impl<D: $crate::driver::Driver> $crate::modules::adc::AdcModule<D> for $device<D> {}
+
+        impl<D: $crate::driver::Driver, const N: u8>
+            $crate::pins::OneShot<$device<D>, u16, $crate::pins::AdcPin<$device<D>, N>>
+            for $device<D>
+        {
+            type Error = $crate::SeesawError<D::I2cError>;
+
+            fn read(
+                &mut self,
+                _pin: &mut $crate::pins::AdcPin<$device<D>, N>,
+            ) -> $crate::pins::nb::Result<u16, Self::Error> {
+                $crate::modules::adc::AdcModule::analog_read(self, N)
+                    .map_err($crate::pins::nb::Error::Other)
+            }
+        }
     };
     ($device:ident, EncoderModule { button_pin: $button_pin:expr }) => {
         impl<D: $crate::driver::Driver> $crate::modules::encoder::EncoderModule<D> for $device<D> {
@@ -77,11 +129,33 @@ macro_rules! impl_device_module {
         impl<D: $crate::driver::Driver> $crate::modules::gpio::GpioModule<D> for $device<D> {}
     };
     ($device:ident, NeopixelModule { num_leds: $num_leds:expr, pin: $pin:expr }) => {
+        impl_device_module! {
+            $device,
+            NeopixelModule {
+                num_leds: $num_leds,
+                pin: $pin,
+                format: $crate::modules::neopixel::PixelFormat::Rgb
+            }
+        }
+    };
+    (
+        $device:ident,
+        NeopixelModule { num_leds: $num_leds:expr, pin: $pin:expr, format: $format:expr }
+    ) => {
         impl<D: $crate::driver::Driver> $crate::modules::neopixel::NeopixelModule<D>
             for $device<D>
         {
             const N_LEDS: u16 = $num_leds;
             const PIN: u8 = $pin;
+            const PIXEL_FORMAT: $crate::modules::neopixel::PixelFormat = $format;
+
+            fn neopixel_brightness(&self) -> u8 {
+                self.3.brightness
+            }
+
+            fn set_neopixel_brightness(&mut self, brightness: u8) {
+                self.3.brightness = brightness;
+            }
         }
     };
     ($device:ident, StatusModule $({})?) => {
@@ -90,4 +164,139 @@ macro_rules! impl_device_module {
     ($device:ident, TimerModule $({})?) => {
         impl<D: $crate::driver::Driver> $crate::modules::timer::TimerModule<D> for $device<D> {}
     };
+    ($device:ident, TouchModule $({})?) => {
+        impl<D: $crate::driver::Driver> $crate::modules::touch::TouchModule<D> for $device<D> {}
+    };
+}
+
+#[cfg(feature = "async")]
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! impl_device_async {
+    (
+        name: $name:ident,
+        hardware_id: $hardware_id:expr,
+        product_id: $product_id:expr,
+        default_addr: $default_addr:expr
+    ) => {
+        impl<D: $crate::driver::DriverAsync> $crate::SeesawDeviceAsync for $name<D> {
+            type Driver = D;
+            type Error = $crate::SeesawError<D::Error>;
+
+            const DEFAULT_ADDR: u8 = $default_addr;
+            const HARDWARE_ID: $crate::common::HardwareId = $hardware_id;
+            const PRODUCT_ID: u16 = $product_id;
+
+            fn addr(&self) -> u8 {
+                self.0
+            }
+
+            fn driver(&mut self) -> &mut D {
+                &mut self.1
+            }
+
+            fn timing(&self) -> &$crate::Timing {
+                &self.2
+            }
+
+            fn new(addr: u8, driver: D) -> Self {
+                Self::new_with_timing(addr, driver, $crate::Timing::for_hardware($hardware_id))
+            }
+
+            fn new_with_default_addr(driver: D) -> Self {
+                Self::new(Self::DEFAULT_ADDR, driver)
+            }
+
+            fn new_with_timing(addr: u8, driver: D, timing: $crate::Timing) -> Self {
+                Self(addr, driver, timing, core::default::Default::default())
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "async"))]
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! impl_device_async {
+    ($($tt:tt)*) => {};
+}
+
+#[cfg(feature = "async")]
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! impl_device_module_async {
+    ($device:ident, AdcModule $({})?) => {
+        impl<D: $crate::driver::DriverAsync> $crate::modules::adc::AdcModuleAsync<D>
+            for $device<D>
+        {
+        }
+    };
+    ($device:ident, EncoderModule { button_pin: $button_pin:expr }) => {
+        impl<D: $crate::driver::DriverAsync> $crate::modules::encoder::EncoderModuleAsync<D>
+            for $device<D>
+        {
+            const ENCODER_BTN_PIN: u8 = $button_pin;
+        }
+    };
+    ($device:ident, GpioModule $({})?) => {
+        impl<D: $crate::driver::DriverAsync> $crate::modules::gpio::GpioModuleAsync<D>
+            for $device<D>
+        {
+        }
+    };
+    ($device:ident, NeopixelModule { num_leds: $num_leds:expr, pin: $pin:expr }) => {
+        impl_device_module_async! {
+            $device,
+            NeopixelModule {
+                num_leds: $num_leds,
+                pin: $pin,
+                format: $crate::modules::neopixel::PixelFormat::Rgb
+            }
+        }
+    };
+    (
+        $device:ident,
+        NeopixelModule { num_leds: $num_leds:expr, pin: $pin:expr, format: $format:expr }
+    ) => {
+        impl<D: $crate::driver::DriverAsync> $crate::modules::neopixel::NeopixelModuleAsync<D>
+            for $device<D>
+        {
+            const N_LEDS: u16 = $num_leds;
+            const PIN: u8 = $pin;
+            const PIXEL_FORMAT: $crate::modules::neopixel::PixelFormat = $format;
+
+            fn neopixel_brightness(&self) -> u8 {
+                self.3.brightness
+            }
+
+            fn set_neopixel_brightness(&mut self, brightness: u8) {
+                self.3.brightness = brightness;
+            }
+        }
+    };
+    ($device:ident, StatusModule $({})?) => {
+        impl<D: $crate::driver::DriverAsync> $crate::modules::status::StatusModuleAsync<D>
+            for $device<D>
+        {
+        }
+    };
+    ($device:ident, TimerModule $({})?) => {
+        impl<D: $crate::driver::DriverAsync> $crate::modules::timer::TimerModuleAsync<D>
+            for $device<D>
+        {
+        }
+    };
+    ($device:ident, TouchModule $({})?) => {
+        impl<D: $crate::driver::DriverAsync> $crate::modules::touch::TouchModuleAsync<D>
+            for $device<D>
+        {
+        }
+    };
+}
+
+#[cfg(not(feature = "async"))]
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! impl_device_module_async {
+    ($($tt:tt)*) => {};
 }
@@ -0,0 +1,82 @@
+//! A scripted driver for unit tests, pairing an I2C transaction mock with a
+//! delay that returns immediately
+use embedded_hal::blocking::{delay, i2c};
+use embedded_hal_mock::eh0::{
+    delay::NoopDelay,
+    i2c::{Mock as I2cMock, Transaction},
+    MockError,
+};
+
+pub(crate) struct MockDriver {
+    i2c: I2cMock,
+    delay: NoopDelay,
+}
+
+impl MockDriver {
+    pub(crate) fn new(expectations: &[Transaction]) -> Self {
+        Self {
+            i2c: I2cMock::new(expectations),
+            delay: NoopDelay::new(),
+        }
+    }
+
+    /// Assert that every expected transaction happened
+    pub(crate) fn done(&mut self) {
+        self.i2c.done()
+    }
+}
+
+/// Clones share their expectations, like handles to the same bus
+impl Clone for MockDriver {
+    fn clone(&self) -> Self {
+        Self {
+            i2c: self.i2c.clone(),
+            delay: NoopDelay::new(),
+        }
+    }
+}
+
+/// Expect a register write of `bytes` to `reg`
+pub(crate) fn write(addr: u8, reg: [u8; 2], bytes: &[u8]) -> Transaction {
+    let mut expected = Vec::from(reg);
+    expected.extend_from_slice(bytes);
+    Transaction::write(addr, expected)
+}
+
+/// Expect a register read of `reg`, responding with `bytes`
+pub(crate) fn read(addr: u8, reg: [u8; 2], bytes: &[u8]) -> [Transaction; 2] {
+    [
+        Transaction::write(addr, Vec::from(reg)),
+        Transaction::read(addr, Vec::from(bytes)),
+    ]
+}
+
+impl i2c::Write for MockDriver {
+    type Error = MockError;
+
+    fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.i2c.write(addr, bytes)
+    }
+}
+
+impl i2c::Read for MockDriver {
+    type Error = MockError;
+
+    fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.i2c.read(addr, buffer)
+    }
+}
+
+impl i2c::WriteRead for MockDriver {
+    type Error = MockError;
+
+    fn write_read(&mut self, addr: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.i2c.write_read(addr, bytes, buffer)
+    }
+}
+
+impl delay::DelayUs<u32> for MockDriver {
+    fn delay_us(&mut self, us: u32) {
+        self.delay.delay_us(us)
+    }
+}
@@ -1,6 +1,9 @@
+#[cfg(feature = "async")]
+use crate::DriverExtAsync;
 use crate::{
     common::{Modules, Reg},
-    DriverExt, HardwareId,
+    pins::AdcPin,
+    DriverExt, HardwareId, PinCapabilities,
 };
 
 /// RO - 8 bits
@@ -39,7 +42,7 @@ const WINTHRESH: &Reg = &[Modules::Adc.into_u8(), 0x05];
 
 /// RO - 16bits
 /// ADC value for channel 0
-const CHANNEL_0: &Reg = &[Modules::Adc.into_u8(), 0x07];
+pub(crate) const CHANNEL_0: &Reg = &[Modules::Adc.into_u8(), 0x07];
 
 /// The ADC provides the ability to measure analog voltages at 10-bit
 /// resolution. The SAMD09 seesaw has 4 ADC inputs, the Attiny8x7 has 11 ADC
@@ -55,22 +58,139 @@ const CHANNEL_0: &Reg = &[Modules::Adc.into_u8(), 0x07];
 ///
 /// Allow a delay of at least 1ms in between sequential ADC reads on different
 /// channels.
+///
+/// `analog_read` waits [`Timing::adc_delay_us`](crate::Timing::adc_delay_us)
+/// between selecting the channel and reading it.
 pub trait AdcModule<D: crate::Driver>: crate::SeesawDevice<Driver = D> {
     fn analog_read(&mut self, pin: u8) -> Result<u16, crate::SeesawError<D::I2cError>> {
-        let pin_offset = match Self::HARDWARE_ID {
-            HardwareId::ATTINY817 => pin,
-            HardwareId::SAMD09 => match pin {
-                2 => 0,
-                3 => 1,
-                4 => 2,
-                5 => 3,
-                _ => 0,
-            },
-        };
+        let reg = channel_reg(Self::HARDWARE_ID, pin)?;
 
         let addr = self.addr();
-        self.driver()
-            .read_u16(addr, &[CHANNEL_0[0], CHANNEL_0[1] + pin_offset])
-            .map_err(crate::SeesawError::I2c)
+        let delay = self.timing().adc_delay_us;
+        self.bus()
+            .with_read_delay(delay)
+            .read_u16(addr, &reg)
+            .map_err(crate::SeesawError::i2c(&reg))
+    }
+
+    /// A handle for sampling pin `N` through `embedded-hal`'s `OneShot`,
+    /// which the device implements. It only compiles for pins with an ADC
+    /// channel on the device's chip.
+    fn adc_pin<const N: u8>(&self) -> AdcPin<Self, N>
+    where
+        Self: Sized,
+    {
+        AdcPin::new()
+    }
+}
+
+#[cfg(feature = "async")]
+pub trait AdcModuleAsync<D: crate::DriverAsync>: crate::SeesawDeviceAsync<Driver = D> {
+    async fn analog_read(&mut self, pin: u8) -> Result<u16, crate::SeesawError<D::Error>> {
+        let reg = channel_reg(Self::HARDWARE_ID, pin)?;
+
+        let addr = self.addr();
+        let delay = self.timing().adc_delay_us;
+        self.bus()
+            .with_read_delay(delay)
+            .read_u16(addr, &reg)
+            .await
+            .map_err(crate::SeesawError::i2c(&reg))
+    }
+}
+
+/// The `CHANNEL` register holding the conversion for `pin`
+pub(crate) fn channel_reg<E>(
+    hardware_id: HardwareId,
+    pin: u8,
+) -> Result<Reg, crate::SeesawError<E>> {
+    PinCapabilities::for_hardware(hardware_id)
+        .adc_channel(pin)
+        .map(|offset| [CHANNEL_0[0], CHANNEL_0[1] + offset])
+        .ok_or(crate::SeesawError::InvalidPin {
+            module: Modules::Adc,
+            pin,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        devices::NeoSlider,
+        mock::{read, MockDriver},
+        SeesawDevice,
+    };
+
+    #[test]
+    fn attiny_channel_is_pin() {
+        let mut dev = NeoSlider::new(
+            0x30,
+            MockDriver::new(&read(0x30, [0x09, 0x07 + 18], &[0x01, 0xFF])),
+        );
+        assert_eq!(dev.analog_read(18).unwrap(), 0x01FF);
+        dev.driver().done();
+    }
+
+    #[test]
+    fn attiny_pin_without_channel() {
+        let mut dev = NeoSlider::new(0x30, MockDriver::new(&[]));
+        assert!(dev.analog_read(9).is_err());
+        dev.driver().done();
+    }
+
+    #[test]
+    fn one_shot_reads_pin() {
+        use embedded_hal::adc::OneShot;
+
+        let mut dev = NeoSlider::new(
+            0x30,
+            MockDriver::new(&read(0x30, [0x09, 0x07 + 18], &[0x02, 0x00])),
+        );
+        let mut pin = dev.adc_pin::<18>();
+        assert_eq!(dev.read(&mut pin).unwrap(), 0x0200);
+        dev.driver().done();
+    }
+
+    #[test]
+    fn samd09_pins_are_remapped() {
+        let expectations = [
+            read(0x49, [0x09, 0x07], &[0, 2]),
+            read(0x49, [0x09, 0x08], &[0, 3]),
+            read(0x49, [0x09, 0x09], &[0, 4]),
+            read(0x49, [0x09, 0x0A], &[0, 5]),
+        ]
+        .concat();
+        let mut dev = samd09::AdcDevice::new(0x49, MockDriver::new(&expectations));
+        for pin in 2..=5 {
+            assert_eq!(dev.analog_read(pin).unwrap(), pin as u16);
+        }
+        dev.driver().done();
+    }
+
+    #[test]
+    fn samd09_pin_without_channel() {
+        let mut dev = samd09::AdcDevice::new(0x49, MockDriver::new(&[]));
+        assert!(matches!(
+            dev.analog_read(6),
+            Err(crate::SeesawError::InvalidPin {
+                module: Modules::Adc,
+                pin: 6
+            })
+        ));
+        dev.driver().done();
+    }
+
+    #[allow(dead_code)]
+    mod samd09 {
+        use crate::HardwareId;
+
+        crate::seesaw_device! {
+            name: AdcDevice,
+            hardware_id: HardwareId::SAMD09,
+            product_id: 0,
+            default_addr: 0x49,
+            modules: [AdcModule]
+        }
     }
 }
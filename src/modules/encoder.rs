@@ -1,4 +1,8 @@
+#[cfg(feature = "async")]
+use super::gpio::GpioModuleAsync;
 use super::gpio::{GpioModule, PinMode};
+#[cfg(feature = "async")]
+use crate::DriverExtAsync;
 use crate::{
     common::{Modules, Reg},
     DriverExt,
@@ -8,15 +12,16 @@ use crate::{
 const STATUS: &Reg = &[Modules::Encoder.into_u8(), 0x00];
 const INT_SET: &Reg = &[Modules::Encoder.into_u8(), 0x10];
 const INT_CLR: &Reg = &[Modules::Encoder.into_u8(), 0x20];
-const POSITION: &Reg = &[Modules::Encoder.into_u8(), 0x30];
-const DELTA: &Reg = &[Modules::Encoder.into_u8(), 0x40];
+pub(crate) const POSITION: &Reg = &[Modules::Encoder.into_u8(), 0x30];
+pub(crate) const DELTA: &Reg = &[Modules::Encoder.into_u8(), 0x40];
 
 pub trait EncoderModule<D: crate::Driver>: GpioModule<D> {
     const ENCODER_BTN_PIN: u8;
 
     fn enable_button(&mut self) -> Result<(), crate::SeesawError<D::I2cError>> {
+        let delay = self.timing().write_delay_us;
         self.set_pin_mode(Self::ENCODER_BTN_PIN, PinMode::InputPullup)
-            .map(|_| self.driver().delay_us(125))
+            .map(|_| self.driver().delay_us(delay))
     }
 
     fn button(&mut self) -> Result<bool, crate::SeesawError<D::I2cError>> {
@@ -25,36 +30,142 @@ pub trait EncoderModule<D: crate::Driver>: GpioModule<D> {
 
     fn delta(&mut self) -> Result<i32, crate::SeesawError<D::I2cError>> {
         let addr = self.addr();
-        self.driver()
+        self.bus()
             .read_i32(addr, DELTA)
-            .map_err(crate::SeesawError::I2c)
+            .map_err(crate::SeesawError::i2c(DELTA))
     }
 
     fn disable_interrupt(&mut self) -> Result<(), crate::SeesawError<D::I2cError>> {
         let addr = self.addr();
-        self.driver()
+        self.bus()
             .write_u8(addr, INT_CLR, 1)
-            .map_err(crate::SeesawError::I2c)
+            .map_err(crate::SeesawError::i2c(INT_CLR))
     }
 
     fn enable_interrupt(&mut self) -> Result<(), crate::SeesawError<D::I2cError>> {
         let addr = self.addr();
-        self.driver()
+        self.bus()
             .write_u8(addr, INT_SET, 1)
-            .map_err(crate::SeesawError::I2c)
+            .map_err(crate::SeesawError::i2c(INT_SET))
     }
 
     fn position(&mut self) -> Result<i32, crate::SeesawError<D::I2cError>> {
         let addr = self.addr();
-        self.driver()
+        self.bus()
             .read_i32(addr, POSITION)
-            .map_err(crate::SeesawError::I2c)
+            .map_err(crate::SeesawError::i2c(POSITION))
     }
 
     fn set_position(&mut self, pos: i32) -> Result<(), crate::SeesawError<D::I2cError>> {
         let addr = self.addr();
-        self.driver()
+        self.bus()
             .write_i32(addr, POSITION, pos)
-            .map_err(crate::SeesawError::I2c)
+            .map_err(crate::SeesawError::i2c(POSITION))
+    }
+}
+
+#[cfg(feature = "async")]
+pub trait EncoderModuleAsync<D: crate::DriverAsync>: GpioModuleAsync<D> {
+    const ENCODER_BTN_PIN: u8;
+
+    async fn enable_button(&mut self) -> Result<(), crate::SeesawError<D::Error>> {
+        let delay = self.timing().write_delay_us;
+        self.set_pin_mode(Self::ENCODER_BTN_PIN, PinMode::InputPullup)
+            .await?;
+        self.driver().delay_us(delay).await;
+        Ok(())
+    }
+
+    async fn button(&mut self) -> Result<bool, crate::SeesawError<D::Error>> {
+        self.digital_read(Self::ENCODER_BTN_PIN).await
+    }
+
+    async fn delta(&mut self) -> Result<i32, crate::SeesawError<D::Error>> {
+        let addr = self.addr();
+        self.bus()
+            .read_i32(addr, DELTA)
+            .await
+            .map_err(crate::SeesawError::i2c(DELTA))
+    }
+
+    async fn disable_interrupt(&mut self) -> Result<(), crate::SeesawError<D::Error>> {
+        let addr = self.addr();
+        self.bus()
+            .write_u8(addr, INT_CLR, 1)
+            .await
+            .map_err(crate::SeesawError::i2c(INT_CLR))
+    }
+
+    async fn enable_interrupt(&mut self) -> Result<(), crate::SeesawError<D::Error>> {
+        let addr = self.addr();
+        self.bus()
+            .write_u8(addr, INT_SET, 1)
+            .await
+            .map_err(crate::SeesawError::i2c(INT_SET))
+    }
+
+    async fn position(&mut self) -> Result<i32, crate::SeesawError<D::Error>> {
+        let addr = self.addr();
+        self.bus()
+            .read_i32(addr, POSITION)
+            .await
+            .map_err(crate::SeesawError::i2c(POSITION))
+    }
+
+    async fn set_position(&mut self, pos: i32) -> Result<(), crate::SeesawError<D::Error>> {
+        let addr = self.addr();
+        self.bus()
+            .write_i32(addr, POSITION, pos)
+            .await
+            .map_err(crate::SeesawError::i2c(POSITION))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        devices::RotaryEncoder,
+        mock::{read, write, MockDriver},
+        SeesawDevice,
+    };
+
+    const ADDR: u8 = 0x36;
+
+    #[test]
+    fn set_position() {
+        let mut dev = RotaryEncoder::new(
+            ADDR,
+            MockDriver::new(&[write(ADDR, [0x11, 0x30], &[0xFF, 0xFF, 0xFF, 0xFE])]),
+        );
+        dev.set_position(-2).unwrap();
+        dev.driver().done();
+    }
+
+    #[test]
+    fn position_and_delta() {
+        let expectations = [
+            read(ADDR, [0x11, 0x30], &[0, 0, 0x01, 0x00]),
+            read(ADDR, [0x11, 0x40], &[0xFF, 0xFF, 0xFF, 0xFD]),
+        ]
+        .concat();
+        let mut dev = RotaryEncoder::new(ADDR, MockDriver::new(&expectations));
+        assert_eq!(dev.position().unwrap(), 256);
+        assert_eq!(dev.delta().unwrap(), -3);
+        dev.driver().done();
+    }
+
+    #[test]
+    fn interrupts() {
+        let mut dev = RotaryEncoder::new(
+            ADDR,
+            MockDriver::new(&[
+                write(ADDR, [0x11, 0x10], &[1]),
+                write(ADDR, [0x11, 0x20], &[1]),
+            ]),
+        );
+        dev.enable_interrupt().unwrap();
+        dev.disable_interrupt().unwrap();
+        dev.driver().done();
     }
 }
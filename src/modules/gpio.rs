@@ -1,12 +1,14 @@
+#[cfg(feature = "async")]
+use crate::DriverExtAsync;
 use crate::{
     common::{Modules, Reg},
-    DriverExt,
+    pins::Pins,
+    DriverExt, HardwareId, PinCapabilities,
 };
 
 /// WO - 32 bits
 /// Writing a 1 to any bit in this register sets the direction of the
 /// corresponding pin to OUTPUT. Writing 0 has no effect.
-#[allow(dead_code)]
 const SET_OUTPUT: &Reg = &[Modules::Gpio.into_u8(), 0x02];
 
 /// WO - 32 bits
@@ -19,42 +21,38 @@ const SET_INPUT: &Reg = &[Modules::Gpio.into_u8(), 0x03];
 /// corresponding pins set LOW. All bits that are set to 1 will
 /// have their corresponding pins set HIGH.
 /// Reading this register reads all pins on PORTA of the seesaw device.
-const GPIO: &Reg = &[Modules::Gpio.into_u8(), 0x04];
+pub(crate) const GPIO: &Reg = &[Modules::Gpio.into_u8(), 0x04];
 
 /// WO - 32 bits
 /// Writing a 1 to any bit in this register writes the corresponding pin
 /// HIGH. Writing 0 has no effect.
-const SET_HIGH: &Reg = &[Modules::Gpio.into_u8(), 0x05];
+pub(crate) const SET_HIGH: &Reg = &[Modules::Gpio.into_u8(), 0x05];
 
 /// WO - 32 bits
 /// Writing a 1 to any bit in this register writes the corresponding pin
 /// LOW. Writing 0 has no effect.
-const SET_LOW: &Reg = &[Modules::Gpio.into_u8(), 0x06];
+pub(crate) const SET_LOW: &Reg = &[Modules::Gpio.into_u8(), 0x06];
 
 /// W0 - 32 bits
 /// Writing a 1 to any bit in this register toggles the corresponding pin.
 /// Writing 0 has no effect.
-#[allow(dead_code)]
-const TOGGLE: &Reg = &[Modules::Gpio.into_u8(), 0x07];
+pub(crate) const TOGGLE: &Reg = &[Modules::Gpio.into_u8(), 0x07];
 
 /// WO - 32 bits
 /// Writing a 1 to any bit in this register enables the interrupt on the
 /// corresponding pin. When the value on this pin changes, the corresponding
 /// bit will be set in the INTFLAG register. Writing 0 has no effect.
-#[allow(dead_code)]
 const INT_ENABLE: &Reg = &[Modules::Gpio.into_u8(), 0x08];
 
 /// WO - 32 bits
 /// Writing a 1 to any bit in this register disables the interrupt on the
 /// corresponding pin. Writing 0 has no effect.
-#[allow(dead_code)]
 const INT_DISABLE: &Reg = &[Modules::Gpio.into_u8(), 0x09];
 
 /// RO - 32 bits
 /// This register hold the status of all GPIO interrupts.
 /// When an interrupt fires, the corresponding bit in this register gets
 /// set. Reading this register clears all interrupts.
-#[allow(dead_code)]
 const INT_FLAG: &Reg = &[Modules::Gpio.into_u8(), 0x0A];
 
 /// WO - 32 bits
@@ -84,15 +82,38 @@ const PULL_DISABLE: &Reg = &[Modules::Gpio.into_u8(), 0x0C];
 /// The module base register address for the GPIO module is 0x01.
 pub trait GpioModule<D: crate::Driver>: crate::SeesawDevice<Driver = D> {
     fn digital_read(&mut self, pin: u8) -> Result<bool, crate::SeesawError<D::I2cError>> {
-        self.digital_read_bulk()
-            .map(|pins| !matches!(pins >> pin & 0x1, 1))
+        let mask = pin_mask(Self::HARDWARE_ID, pin)?;
+        self.digital_read_bulk().map(|pins| pins & mask == 0)
     }
 
     fn digital_read_bulk(&mut self) -> Result<u32, crate::SeesawError<D::I2cError>> {
         let addr = self.addr();
-        self.driver()
+        self.bus()
             .read_u32(addr, GPIO)
-            .map_err(crate::SeesawError::I2c)
+            .map_err(crate::SeesawError::i2c(GPIO))
+    }
+
+    fn digital_write(
+        &mut self,
+        pin: u8,
+        value: bool,
+    ) -> Result<(), crate::SeesawError<D::I2cError>> {
+        let mask = pin_mask(Self::HARDWARE_ID, pin)?;
+        self.digital_write_bulk(mask, value)
+    }
+
+    /// Drive every pin set in `pins` high or low
+    fn digital_write_bulk(
+        &mut self,
+        pins: u32,
+        value: bool,
+    ) -> Result<(), crate::SeesawError<D::I2cError>> {
+        check_pins(Self::HARDWARE_ID, pins)?;
+        let addr = self.addr();
+        let reg = if value { SET_HIGH } else { SET_LOW };
+        self.bus()
+            .write_u32(addr, reg, pins)
+            .map_err(crate::SeesawError::i2c(reg))
     }
 
     fn set_pin_mode(
@@ -100,7 +121,8 @@ pub trait GpioModule<D: crate::Driver>: crate::SeesawDevice<Driver = D> {
         pin: u8,
         mode: PinMode,
     ) -> Result<(), crate::SeesawError<D::I2cError>> {
-        self.set_pin_mode_bulk(1 << pin, mode)
+        let mask = pin_mask(Self::HARDWARE_ID, pin)?;
+        self.set_pin_mode_bulk(mask, mode)
     }
 
     fn set_pin_mode_bulk(
@@ -108,27 +130,238 @@ pub trait GpioModule<D: crate::Driver>: crate::SeesawDevice<Driver = D> {
         pins: u32,
         mode: PinMode,
     ) -> Result<(), crate::SeesawError<D::I2cError>> {
+        check_pins(Self::HARDWARE_ID, pins)?;
+        let addr = self.addr();
+        write_pin_mode(&mut self.bus(), addr, pins, mode)
+    }
+
+    /// Raise the interrupt line when `pin` changes, or stop raising it
+    fn set_pin_interrupt(
+        &mut self,
+        pin: u8,
+        enabled: bool,
+    ) -> Result<(), crate::SeesawError<D::I2cError>> {
+        let mask = interrupt_mask(Self::HARDWARE_ID, pin)?;
+        self.set_pin_interrupt_bulk(mask, enabled)
+    }
+
+    fn set_pin_interrupt_bulk(
+        &mut self,
+        pins: u32,
+        enabled: bool,
+    ) -> Result<(), crate::SeesawError<D::I2cError>> {
+        check_interrupt_pins(Self::HARDWARE_ID, pins)?;
+        let addr = self.addr();
+        let reg = if enabled { INT_ENABLE } else { INT_DISABLE };
+        self.bus()
+            .write_u32(addr, reg, pins)
+            .map_err(crate::SeesawError::i2c(reg))
+    }
+
+    /// The pins that changed since the flags were last read. Reading them
+    /// clears them.
+    fn interrupt_flags(&mut self) -> Result<u32, crate::SeesawError<D::I2cError>> {
+        let addr = self.addr();
+        self.bus()
+            .read_u32(addr, INT_FLAG)
+            .map_err(crate::SeesawError::i2c(INT_FLAG))
+    }
+
+    /// Split the GPIO into a handle per pin that implements the
+    /// `embedded-hal` pin traits. Each handle gets a clone of the driver, so
+    /// this is meant for shared drivers like
+    /// [`BusProxy`](crate::bus::BusProxy).
+    fn split(&mut self) -> Pins<D>
+    where
+        D: Clone,
+    {
+        let addr = self.addr();
+        let timing = *self.timing();
+        Pins::new(addr, self.driver(), timing, Self::HARDWARE_ID)
+    }
+}
+
+/// The bit for `pin` in the GPIO registers, if it's a GPIO pin on the chip
+pub(crate) fn pin_mask<E>(hardware_id: HardwareId, pin: u8) -> Result<u32, crate::SeesawError<E>> {
+    if PinCapabilities::for_hardware(hardware_id).is_digital(pin) {
+        Ok(1 << pin)
+    } else {
+        Err(crate::SeesawError::InvalidPin {
+            module: Modules::Gpio,
+            pin,
+        })
+    }
+}
+
+/// Check that every pin set in `pins` is a GPIO pin on the chip, reporting
+/// the lowest one that isn't
+pub(crate) fn check_pins<E>(
+    hardware_id: HardwareId,
+    pins: u32,
+) -> Result<(), crate::SeesawError<E>> {
+    check_mask(PinCapabilities::for_hardware(hardware_id).digital, pins)
+}
+
+/// The bit for `pin` in the interrupt registers, if it can raise interrupts
+/// on the chip
+fn interrupt_mask<E>(hardware_id: HardwareId, pin: u8) -> Result<u32, crate::SeesawError<E>> {
+    if PinCapabilities::for_hardware(hardware_id).is_interrupt(pin) {
+        Ok(1 << pin)
+    } else {
+        Err(crate::SeesawError::InvalidPin {
+            module: Modules::Gpio,
+            pin,
+        })
+    }
+}
+
+/// Check that every pin set in `pins` can raise interrupts on the chip
+fn check_interrupt_pins<E>(
+    hardware_id: HardwareId,
+    pins: u32,
+) -> Result<(), crate::SeesawError<E>> {
+    check_mask(PinCapabilities::for_hardware(hardware_id).interrupt, pins)
+}
+
+/// Check that `pins` is within `allowed`, reporting the lowest pin that isn't
+fn check_mask<E>(allowed: u32, pins: u32) -> Result<(), crate::SeesawError<E>> {
+    match pins & !allowed {
+        0 => Ok(()),
+        invalid => Err(crate::SeesawError::InvalidPin {
+            module: Modules::Gpio,
+            pin: invalid.trailing_zeros() as u8,
+        }),
+    }
+}
+
+/// Set the mode of every pin set in `pins`
+pub(crate) fn write_pin_mode<B: DriverExt>(
+    bus: &mut B,
+    addr: u8,
+    pins: u32,
+    mode: PinMode,
+) -> Result<(), crate::SeesawError<B::Error>> {
+    for reg in mode_regs(mode)? {
+        bus.write_u32(addr, reg, pins)
+            .map_err(crate::SeesawError::i2c(reg))?;
+    }
+    Ok(())
+}
+
+/// The registers to write a pin mask to, in order, to put the pins in `mode`.
+/// Shared by the blocking and async paths so they set modes the same way.
+fn mode_regs<E>(mode: PinMode) -> Result<&'static [&'static Reg], crate::SeesawError<E>> {
+    match mode {
+        PinMode::Output => Ok(&[SET_OUTPUT]),
+        PinMode::Input => Ok(&[SET_INPUT]),
+        PinMode::InputPullup => Ok(&[SET_INPUT, PULL_ENABLE, SET_HIGH]),
+        PinMode::InputPulldown => Ok(&[SET_INPUT, PULL_ENABLE, SET_LOW]),
+        _ => Err(crate::SeesawError::UnsupportedMode(mode)),
+    }
+}
+
+#[cfg(feature = "async")]
+pub trait GpioModuleAsync<D: crate::DriverAsync>: crate::SeesawDeviceAsync<Driver = D> {
+    async fn digital_read(&mut self, pin: u8) -> Result<bool, crate::SeesawError<D::Error>> {
+        let mask = pin_mask(Self::HARDWARE_ID, pin)?;
+        self.digital_read_bulk().await.map(|pins| pins & mask == 0)
+    }
+
+    async fn digital_read_bulk(&mut self) -> Result<u32, crate::SeesawError<D::Error>> {
+        let addr = self.addr();
+        self.bus()
+            .read_u32(addr, GPIO)
+            .await
+            .map_err(crate::SeesawError::i2c(GPIO))
+    }
+
+    async fn digital_write(
+        &mut self,
+        pin: u8,
+        value: bool,
+    ) -> Result<(), crate::SeesawError<D::Error>> {
+        let mask = pin_mask(Self::HARDWARE_ID, pin)?;
+        self.digital_write_bulk(mask, value).await
+    }
+
+    /// Drive every pin set in `pins` high or low
+    async fn digital_write_bulk(
+        &mut self,
+        pins: u32,
+        value: bool,
+    ) -> Result<(), crate::SeesawError<D::Error>> {
+        check_pins(Self::HARDWARE_ID, pins)?;
+        let addr = self.addr();
+        let reg = if value { SET_HIGH } else { SET_LOW };
+        self.bus()
+            .write_u32(addr, reg, pins)
+            .await
+            .map_err(crate::SeesawError::i2c(reg))
+    }
+
+    async fn set_pin_mode(
+        &mut self,
+        pin: u8,
+        mode: PinMode,
+    ) -> Result<(), crate::SeesawError<D::Error>> {
+        let mask = pin_mask(Self::HARDWARE_ID, pin)?;
+        self.set_pin_mode_bulk(mask, mode).await
+    }
+
+    async fn set_pin_mode_bulk(
+        &mut self,
+        pins: u32,
+        mode: PinMode,
+    ) -> Result<(), crate::SeesawError<D::Error>> {
+        check_pins(Self::HARDWARE_ID, pins)?;
+        let regs = mode_regs(mode)?;
         let addr = self.addr();
-        let bus = self.driver();
-
-        match mode {
-            PinMode::Output => bus.write_u32(addr, GPIO, pins),
-            PinMode::Input => bus.write_u32(addr, SET_INPUT, pins),
-            PinMode::InputPullup => bus
-                .write_u32(addr, SET_INPUT, pins)
-                .and_then(|_| bus.write_u32(addr, PULL_ENABLE, pins))
-                .and_then(|_| bus.write_u32(addr, SET_HIGH, pins)),
-            PinMode::InputPulldown => bus
-                .write_u32(addr, SET_INPUT, pins)
-                .and_then(|_| bus.write_u32(addr, PULL_ENABLE, pins))
-                .and_then(|_| bus.write_u32(addr, SET_LOW, pins)),
-            _ => unimplemented!("Other pins modes are not supported."),
+        for reg in regs {
+            self.bus()
+                .write_u32(addr, reg, pins)
+                .await
+                .map_err(crate::SeesawError::i2c(reg))?;
         }
-        .map_err(crate::SeesawError::I2c)
+        Ok(())
+    }
+
+    /// Raise the interrupt line when `pin` changes, or stop raising it
+    async fn set_pin_interrupt(
+        &mut self,
+        pin: u8,
+        enabled: bool,
+    ) -> Result<(), crate::SeesawError<D::Error>> {
+        let mask = interrupt_mask(Self::HARDWARE_ID, pin)?;
+        self.set_pin_interrupt_bulk(mask, enabled).await
+    }
+
+    async fn set_pin_interrupt_bulk(
+        &mut self,
+        pins: u32,
+        enabled: bool,
+    ) -> Result<(), crate::SeesawError<D::Error>> {
+        check_interrupt_pins(Self::HARDWARE_ID, pins)?;
+        let addr = self.addr();
+        let reg = if enabled { INT_ENABLE } else { INT_DISABLE };
+        self.bus()
+            .write_u32(addr, reg, pins)
+            .await
+            .map_err(crate::SeesawError::i2c(reg))
+    }
+
+    /// The pins that changed since the flags were last read. Reading them
+    /// clears them.
+    async fn interrupt_flags(&mut self) -> Result<u32, crate::SeesawError<D::Error>> {
+        let addr = self.addr();
+        self.bus()
+            .read_u32(addr, INT_FLAG)
+            .await
+            .map_err(crate::SeesawError::i2c(INT_FLAG))
     }
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(u8)]
 pub enum PinMode {
     Input = 0x01,
@@ -156,6 +389,7 @@ impl From<PinMode> for u8 {
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(u8)]
 pub enum InterruptMode {
     Disabled = 0x00,
@@ -173,3 +407,159 @@ impl From<InterruptMode> for u8 {
         value as u8
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        devices::NeoKey1x4,
+        mock::{read, write, MockDriver},
+        SeesawDevice,
+    };
+
+    const ADDR: u8 = 0x30;
+
+    #[test]
+    fn set_pin_mode_bulk_output() {
+        let mut dev = NeoKey1x4::new(
+            ADDR,
+            MockDriver::new(&[write(ADDR, [0x01, 0x02], &[0, 0, 0, 0b1100])]),
+        );
+        dev.set_pin_mode_bulk(0b1100, PinMode::Output).unwrap();
+        dev.driver().done();
+    }
+
+    #[test]
+    fn set_pin_mode_bulk_input() {
+        let mut dev = NeoKey1x4::new(
+            ADDR,
+            MockDriver::new(&[write(ADDR, [0x01, 0x03], &[0, 0, 0, 0b1100])]),
+        );
+        dev.set_pin_mode_bulk(0b1100, PinMode::Input).unwrap();
+        dev.driver().done();
+    }
+
+    #[test]
+    fn set_pin_mode_bulk_input_pullup() {
+        let pins = [0, 0, 0, 0xF0];
+        let mut dev = NeoKey1x4::new(
+            ADDR,
+            MockDriver::new(&[
+                write(ADDR, [0x01, 0x03], &pins),
+                write(ADDR, [0x01, 0x0B], &pins),
+                write(ADDR, [0x01, 0x05], &pins),
+            ]),
+        );
+        dev.set_pin_mode_bulk(0xF0, PinMode::InputPullup).unwrap();
+        dev.driver().done();
+    }
+
+    #[test]
+    fn set_pin_mode_bulk_input_pulldown() {
+        let pins = [0x01, 0, 0, 0];
+        let mut dev = NeoKey1x4::new(
+            ADDR,
+            MockDriver::new(&[
+                write(ADDR, [0x01, 0x03], &pins),
+                write(ADDR, [0x01, 0x0B], &pins),
+                write(ADDR, [0x01, 0x06], &pins),
+            ]),
+        );
+        dev.set_pin_mode_bulk(1 << 24, PinMode::InputPulldown)
+            .unwrap();
+        dev.driver().done();
+    }
+
+    #[test]
+    fn set_pin_mode_bulk_unsupported() {
+        let mut dev = NeoKey1x4::new(ADDR, MockDriver::new(&[]));
+        assert!(matches!(
+            dev.set_pin_mode_bulk(0b1100, PinMode::Analog),
+            Err(crate::SeesawError::UnsupportedMode(PinMode::Analog))
+        ));
+        dev.driver().done();
+    }
+
+    #[test]
+    fn pins_missing_from_the_chip_are_rejected() {
+        let mut dev = NeoKey1x4::new(ADDR, MockDriver::new(&[]));
+        assert!(matches!(
+            dev.digital_write(12, true),
+            Err(crate::SeesawError::InvalidPin {
+                module: Modules::Gpio,
+                pin: 12
+            })
+        ));
+        assert!(matches!(
+            dev.set_pin_mode_bulk(1 << 31 | 1 << 30 | 1 << 4, PinMode::Output),
+            Err(crate::SeesawError::InvalidPin {
+                module: Modules::Gpio,
+                pin: 30
+            })
+        ));
+        assert!(dev.digital_read(40).is_err());
+        dev.driver().done();
+    }
+
+    #[test]
+    fn digital_read_is_active_low() {
+        let levels = [0, 0, 0, 0b1110_0000];
+        let mut dev = NeoKey1x4::new(
+            ADDR,
+            MockDriver::new(
+                &[
+                    read(ADDR, [0x01, 0x04], &levels),
+                    read(ADDR, [0x01, 0x04], &levels),
+                ]
+                .concat(),
+            ),
+        );
+        assert!(dev.digital_read(4).unwrap());
+        assert!(!dev.digital_read(5).unwrap());
+        dev.driver().done();
+    }
+
+    #[test]
+    fn pin_interrupts() {
+        let mut dev = NeoKey1x4::new(
+            ADDR,
+            MockDriver::new(
+                &[
+                    vec![
+                        write(ADDR, [0x01, 0x08], &[0, 0, 0, 0b1_0000]),
+                        write(ADDR, [0x01, 0x09], &[0, 0, 0, 0b1_0000]),
+                    ],
+                    read(ADDR, [0x01, 0x0A], &[0, 0, 0, 0b10_0000]).to_vec(),
+                ]
+                .concat(),
+            ),
+        );
+        dev.set_pin_interrupt(4, true).unwrap();
+        dev.set_pin_interrupt(4, false).unwrap();
+        assert!(matches!(
+            dev.set_pin_interrupt(12, true),
+            Err(crate::SeesawError::InvalidPin {
+                module: Modules::Gpio,
+                pin: 12
+            })
+        ));
+        assert!(dev.set_pin_interrupt_bulk(1 << 31, true).is_err());
+        assert_eq!(dev.interrupt_flags().unwrap(), 0b10_0000);
+        dev.driver().done();
+    }
+
+    #[test]
+    fn digital_write() {
+        let mut dev = NeoKey1x4::new(
+            ADDR,
+            MockDriver::new(&[
+                write(ADDR, [0x01, 0x05], &[0, 0, 0, 0b1000]),
+                write(ADDR, [0x01, 0x06], &[0, 0, 0, 0b1000]),
+            ]),
+        );
+        dev.digital_write(3, true)
+            .and_then(|_| dev.digital_write(3, false))
+            .unwrap();
+        dev.driver().done();
+    }
+}
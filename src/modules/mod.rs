@@ -4,3 +4,4 @@ pub mod gpio;
 pub mod neopixel;
 pub mod status;
 pub mod timer;
+pub mod touch;
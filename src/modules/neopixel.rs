@@ -1,29 +1,31 @@
 use crate::{
-    common::{Modules, Reg},
-    driver::Driver,
-    DriverExt, SeesawDevice, SeesawError,
+    common::{HardwareId, Modules, Reg},
+    driver::{Driver, MAX_WRITE_LEN},
+    DriverExt, Rgb, Rgbw, SeesawDevice, SeesawError,
 };
+#[cfg(feature = "async")]
+use crate::{driver::DriverAsync, DriverExtAsync, SeesawDeviceAsync};
 
 /// WO - 8 bits
 /// This register sets the pin number (PORTA) that is used for the NeoPixel
 /// output.
-const SET_PIN: &Reg = &[Modules::Neopixel.into_u8(), 0x01];
+pub(crate) const SET_PIN: &Reg = &[Modules::Neopixel.into_u8(), 0x01];
 /// WO - 8 bits
 /// The protocol speed. (see `NeopixelSpeed`) Default is 800khz.
 const SET_SPEED: &Reg = &[Modules::Neopixel.into_u8(), 0x02];
 /// WO - 16 bits
 /// The number of bytes currently used for the pixel array. This is
 /// dependent on when the pixels you are using are RGB or RGBW.
-const SET_LEN: &Reg = &[Modules::Neopixel.into_u8(), 0x03];
+pub(crate) const SET_LEN: &Reg = &[Modules::Neopixel.into_u8(), 0x03];
 /// WO - 256 bits (32 bytes)
 /// The data buffer. The first 2 bytes are the start address, and the data
 /// to write follows. Data should be written in blocks of maximum size 30
 /// bytes at a time.
-const SET_BUF: &Reg = &[Modules::Neopixel.into_u8(), 0x04];
+pub(crate) const SET_BUF: &Reg = &[Modules::Neopixel.into_u8(), 0x04];
 /// W0 - Zero bits
 /// Sending the SHOW command will cause the output to update. There's no
 /// arguments/data after the command.
-const SHOW: &Reg = &[Modules::Neopixel.into_u8(), 0x05];
+pub(crate) const SHOW: &Reg = &[Modules::Neopixel.into_u8(), 0x05];
 
 pub trait NeopixelModule<D: Driver>: SeesawDevice<Driver = D> {
     const PIN: u8;
@@ -31,23 +33,28 @@ pub trait NeopixelModule<D: Driver>: SeesawDevice<Driver = D> {
     /// The number of neopixels on the device
     const N_LEDS: u16 = 1;
 
+    /// The color channels each neopixel has
+    const PIXEL_FORMAT: PixelFormat = PixelFormat::Rgb;
+
     fn enable_neopixel(&mut self) -> Result<(), SeesawError<D::I2cError>> {
+        let delay = self.timing().neopixel_delay_us;
         let addr = self.addr();
 
-        self.driver()
+        self.bus()
             .write_u8(addr, SET_PIN, Self::PIN)
-            .and_then(|_| {
-                self.driver().delay_us(10_000);
-                self.driver().write_u16(addr, SET_LEN, 3 * Self::N_LEDS)
-            })
-            .map(|_| self.driver().delay_us(10_000))
-            .map_err(SeesawError::I2c)
+            .map_err(SeesawError::i2c(SET_PIN))?;
+        self.driver().delay_us(delay);
+        self.bus()
+            .write_u16(addr, SET_LEN, Self::PIXEL_FORMAT.buffer_len(Self::N_LEDS))
+            .map(|_| self.driver().delay_us(delay))
+            .map_err(SeesawError::i2c(SET_LEN))
     }
 
     fn set_neopixel_speed(&mut self, speed: NeopixelSpeed) -> Result<(), SeesawError<D::I2cError>> {
+        let delay = self.timing().neopixel_delay_us;
         let addr = self.addr();
 
-        self.driver()
+        self.bus()
             .write_u8(
                 addr,
                 SET_SPEED,
@@ -56,10 +63,18 @@ pub trait NeopixelModule<D: Driver>: SeesawDevice<Driver = D> {
                     NeopixelSpeed::Khz800 => 1,
                 },
             )
-            .map(|_| self.driver().delay_us(10_000))
-            .map_err(SeesawError::I2c)
+            .map(|_| self.driver().delay_us(delay))
+            .map_err(SeesawError::i2c(SET_SPEED))
     }
 
+    /// How bright the pixels are, out of 255. Every color written is scaled by
+    /// it, so 255 writes colors as they are.
+    fn neopixel_brightness(&self) -> u8;
+
+    /// Set the brightness colors written from now on are scaled by. Colors
+    /// already written keep theirs.
+    fn set_neopixel_brightness(&mut self, brightness: u8);
+
     fn set_neopixel_color(&mut self, r: u8, g: u8, b: u8) -> Result<(), SeesawError<D::I2cError>> {
         self.set_nth_neopixel_color(0, r, g, b)
     }
@@ -71,52 +86,555 @@ pub trait NeopixelModule<D: Driver>: SeesawDevice<Driver = D> {
         g: u8,
         b: u8,
     ) -> Result<(), SeesawError<D::I2cError>> {
-        assert!(n < Self::N_LEDS);
-        let [zero, one] = u16::to_be_bytes(3 * n);
+        self.set_nth_neopixel_rgb(n, Rgb::new(r, g, b))
+    }
+
+    /// Set the colors of the first `colors.len()` neopixels. Colors are
+    /// written in as few register writes as possible.
+    fn set_neopixel_colors(
+        &mut self,
+        colors: &[(u8, u8, u8)],
+    ) -> Result<(), SeesawError<D::I2cError>> {
+        check_len(colors.len(), Self::N_LEDS)?;
+        let addr = self.addr();
+        let brightness = self.neopixel_brightness();
+        write_colors(
+            &mut self.bus(),
+            addr,
+            colors,
+            Self::PIXEL_FORMAT,
+            brightness,
+        )
+    }
+
+    fn set_neopixel_rgb(&mut self, color: Rgb) -> Result<(), SeesawError<D::I2cError>> {
+        self.set_nth_neopixel_rgb(0, color)
+    }
+
+    fn set_nth_neopixel_rgb(&mut self, n: u16, color: Rgb) -> Result<(), SeesawError<D::I2cError>> {
+        self.set_nth_neopixel_rgbw(n, color.into())
+    }
+
+    /// [`set_neopixel_colors`](Self::set_neopixel_colors), from [`Rgb`]s
+    fn set_neopixel_rgbs(&mut self, colors: &[Rgb]) -> Result<(), SeesawError<D::I2cError>> {
+        check_len(colors.len(), Self::N_LEDS)?;
         let addr = self.addr();
+        let brightness = self.neopixel_brightness();
+        write_colors(
+            &mut self.bus(),
+            addr,
+            colors,
+            Self::PIXEL_FORMAT,
+            brightness,
+        )
+    }
 
-        self.driver()
-            .register_write(addr, SET_BUF, &[zero, one, r, g, b, 0x00])
-            .map_err(SeesawError::I2c)
+    fn set_neopixel_rgbw(&mut self, color: Rgbw) -> Result<(), SeesawError<D::I2cError>> {
+        self.set_nth_neopixel_rgbw(0, color)
     }
 
-    fn set_neopixel_colors(
+    /// Set the color of the `n`th neopixel. On RGB pixels the white is mixed
+    /// into the red, green and blue.
+    fn set_nth_neopixel_rgbw(
         &mut self,
-        colors: &[(u8, u8, u8); Self::N_LEDS as usize],
-    ) -> Result<(), SeesawError<D::I2cError>>
-    where
-        [(); Self::N_LEDS as usize]: Sized,
-    {
+        n: u16,
+        color: Rgbw,
+    ) -> Result<(), SeesawError<D::I2cError>> {
+        check_index(n, Self::N_LEDS)?;
         let addr = self.addr();
+        let brightness = self.neopixel_brightness();
+        let mut buffer = [0u8; MAX_WRITE_LEN];
+        let len = fill_buffer(
+            &mut buffer,
+            Self::PIXEL_FORMAT.buffer_len(n),
+            &[color],
+            Self::PIXEL_FORMAT,
+            brightness,
+        );
 
-        (0..Self::N_LEDS)
-            .into_iter()
-            .try_for_each(|n| {
-                let [zero, one] = u16::to_be_bytes(3 * n);
-                let color = colors[n as usize];
-                self.driver().register_write(
-                    addr,
-                    SET_BUF,
-                    &[zero, one, color.0, color.1, color.2, 0x00],
-                )
-            })
-            .map_err(SeesawError::I2c)
+        self.bus().register_write(addr, SET_BUF, &buffer[..len])
+    }
+
+    /// [`set_neopixel_colors`](Self::set_neopixel_colors), from [`Rgbw`]s
+    fn set_neopixel_rgbws(&mut self, colors: &[Rgbw]) -> Result<(), SeesawError<D::I2cError>> {
+        check_len(colors.len(), Self::N_LEDS)?;
+        let addr = self.addr();
+        let brightness = self.neopixel_brightness();
+        write_colors(
+            &mut self.bus(),
+            addr,
+            colors,
+            Self::PIXEL_FORMAT,
+            brightness,
+        )
     }
 
     fn sync_neopixel(&mut self) -> Result<(), SeesawError<D::I2cError>> {
+        let delay = self.timing().write_delay_us;
         let addr = self.addr();
 
-        self.driver()
+        self.bus()
             .register_write(addr, SHOW, &[])
-            .map(|_| self.driver().delay_us(125))
-            .map_err(SeesawError::I2c)
+            .map(|_| self.driver().delay_us(delay))
+    }
+}
+
+#[cfg(feature = "async")]
+pub trait NeopixelModuleAsync<D: DriverAsync>: SeesawDeviceAsync<Driver = D> {
+    const PIN: u8;
+
+    /// The number of neopixels on the device
+    const N_LEDS: u16 = 1;
+
+    /// The color channels each neopixel has
+    const PIXEL_FORMAT: PixelFormat = PixelFormat::Rgb;
+
+    async fn enable_neopixel(&mut self) -> Result<(), SeesawError<D::Error>> {
+        let delay = self.timing().neopixel_delay_us;
+        let addr = self.addr();
+
+        self.bus()
+            .write_u8(addr, SET_PIN, Self::PIN)
+            .await
+            .map_err(SeesawError::i2c(SET_PIN))?;
+        self.driver().delay_us(delay).await;
+        self.bus()
+            .write_u16(addr, SET_LEN, Self::PIXEL_FORMAT.buffer_len(Self::N_LEDS))
+            .await
+            .map_err(SeesawError::i2c(SET_LEN))?;
+        self.driver().delay_us(delay).await;
+        Ok(())
+    }
+
+    async fn set_neopixel_speed(
+        &mut self,
+        speed: NeopixelSpeed,
+    ) -> Result<(), SeesawError<D::Error>> {
+        let delay = self.timing().neopixel_delay_us;
+        let addr = self.addr();
+
+        self.bus()
+            .write_u8(
+                addr,
+                SET_SPEED,
+                match speed {
+                    NeopixelSpeed::Khz400 => 0,
+                    NeopixelSpeed::Khz800 => 1,
+                },
+            )
+            .await
+            .map_err(SeesawError::i2c(SET_SPEED))?;
+        self.driver().delay_us(delay).await;
+        Ok(())
+    }
+
+    /// How bright the pixels are, out of 255. Every color written is scaled by
+    /// it, so 255 writes colors as they are.
+    fn neopixel_brightness(&self) -> u8;
+
+    /// Set the brightness colors written from now on are scaled by. Colors
+    /// already written keep theirs.
+    fn set_neopixel_brightness(&mut self, brightness: u8);
+
+    async fn set_neopixel_color(
+        &mut self,
+        r: u8,
+        g: u8,
+        b: u8,
+    ) -> Result<(), SeesawError<D::Error>> {
+        self.set_nth_neopixel_color(0, r, g, b).await
+    }
+
+    async fn set_nth_neopixel_color(
+        &mut self,
+        n: u16,
+        r: u8,
+        g: u8,
+        b: u8,
+    ) -> Result<(), SeesawError<D::Error>> {
+        self.set_nth_neopixel_rgb(n, Rgb::new(r, g, b)).await
+    }
+
+    /// Set the colors of the first `colors.len()` neopixels. Colors are
+    /// written in as few register writes as possible.
+    async fn set_neopixel_colors(
+        &mut self,
+        colors: &[(u8, u8, u8)],
+    ) -> Result<(), SeesawError<D::Error>> {
+        write_colors_async(self, colors).await
+    }
+
+    async fn set_neopixel_rgb(&mut self, color: Rgb) -> Result<(), SeesawError<D::Error>> {
+        self.set_nth_neopixel_rgb(0, color).await
+    }
+
+    async fn set_nth_neopixel_rgb(
+        &mut self,
+        n: u16,
+        color: Rgb,
+    ) -> Result<(), SeesawError<D::Error>> {
+        self.set_nth_neopixel_rgbw(n, color.into()).await
+    }
+
+    /// [`set_neopixel_colors`](Self::set_neopixel_colors), from [`Rgb`]s
+    async fn set_neopixel_rgbs(&mut self, colors: &[Rgb]) -> Result<(), SeesawError<D::Error>> {
+        write_colors_async(self, colors).await
+    }
+
+    async fn set_neopixel_rgbw(&mut self, color: Rgbw) -> Result<(), SeesawError<D::Error>> {
+        self.set_nth_neopixel_rgbw(0, color).await
+    }
+
+    /// Set the color of the `n`th neopixel. On RGB pixels the white is mixed
+    /// into the red, green and blue.
+    async fn set_nth_neopixel_rgbw(
+        &mut self,
+        n: u16,
+        color: Rgbw,
+    ) -> Result<(), SeesawError<D::Error>> {
+        check_index(n, Self::N_LEDS)?;
+        let addr = self.addr();
+        let brightness = self.neopixel_brightness();
+        let mut buffer = [0u8; MAX_WRITE_LEN];
+        let len = fill_buffer(
+            &mut buffer,
+            Self::PIXEL_FORMAT.buffer_len(n),
+            &[color],
+            Self::PIXEL_FORMAT,
+            brightness,
+        );
+
+        self.bus()
+            .register_write(addr, SET_BUF, &buffer[..len])
+            .await
+    }
+
+    /// [`set_neopixel_colors`](Self::set_neopixel_colors), from [`Rgbw`]s
+    async fn set_neopixel_rgbws(&mut self, colors: &[Rgbw]) -> Result<(), SeesawError<D::Error>> {
+        write_colors_async(self, colors).await
+    }
+
+    async fn sync_neopixel(&mut self) -> Result<(), SeesawError<D::Error>> {
+        let delay = self.timing().write_delay_us;
+        let addr = self.addr();
+
+        self.bus().register_write(addr, SHOW, &[]).await?;
+        self.driver().delay_us(delay).await;
+        Ok(())
+    }
+}
+
+/// Write `colors` to the start of the pixel buffer, as `format` pixels at
+/// `brightness`
+pub(crate) fn write_colors<B: DriverExt, C: Copy + Into<Rgbw>>(
+    bus: &mut B,
+    addr: u8,
+    colors: &[C],
+    format: PixelFormat,
+    brightness: u8,
+) -> Result<(), SeesawError<B::Error>> {
+    colors
+        .chunks(format.pixels_per_write())
+        .enumerate()
+        .try_for_each(|(i, chunk)| {
+            let mut buffer = [0u8; MAX_WRITE_LEN];
+            let len = fill_buffer(
+                &mut buffer,
+                format.chunk_start(i),
+                chunk,
+                format,
+                brightness,
+            );
+            bus.register_write(addr, SET_BUF, &buffer[..len])
+        })
+}
+
+#[cfg(feature = "async")]
+async fn write_colors_async<D, DEV, C>(
+    dev: &mut DEV,
+    colors: &[C],
+) -> Result<(), SeesawError<D::Error>>
+where
+    D: DriverAsync,
+    DEV: NeopixelModuleAsync<D> + ?Sized,
+    C: Copy + Into<Rgbw>,
+{
+    check_len(colors.len(), DEV::N_LEDS)?;
+    let addr = dev.addr();
+    let brightness = dev.neopixel_brightness();
+    let format = DEV::PIXEL_FORMAT;
+
+    for (i, chunk) in colors.chunks(format.pixels_per_write()).enumerate() {
+        let mut buffer = [0u8; MAX_WRITE_LEN];
+        let len = fill_buffer(
+            &mut buffer,
+            format.chunk_start(i),
+            chunk,
+            format,
+            brightness,
+        );
+        dev.bus()
+            .register_write(addr, SET_BUF, &buffer[..len])
+            .await?;
+    }
+    Ok(())
+}
+
+/// The most RGB pixels the seesaw firmware on each chip has buffer space for
+pub const fn max_pixels(hardware_id: HardwareId) -> u16 {
+    match hardware_id {
+        HardwareId::ATTINY817 => 60,
+        HardwareId::SAMD09 => 170,
+    }
+}
+
+/// Check that pixel `n` is on a strip of `len` pixels
+pub(crate) fn check_index<E>(n: u16, len: u16) -> Result<(), SeesawError<E>> {
+    if n < len {
+        Ok(())
+    } else {
+        Err(SeesawError::PixelOutOfRange { index: n, len })
+    }
+}
+
+/// Check that `count` colors fit on a strip of `len` pixels
+pub(crate) fn check_len<E>(count: usize, len: u16) -> Result<(), SeesawError<E>> {
+    if count <= len as usize {
+        Ok(())
+    } else {
+        Err(SeesawError::PixelOutOfRange { index: len, len })
+    }
+}
+
+/// Fill `buffer` with the byte offset `start` and `colors` as `format` pixels
+/// scaled by `brightness`, returning the number of bytes used
+fn fill_buffer<C: Copy + Into<Rgbw>>(
+    buffer: &mut [u8; MAX_WRITE_LEN],
+    start: u16,
+    colors: &[C],
+    format: PixelFormat,
+    brightness: u8,
+) -> usize {
+    let size = format.bytes_per_pixel() as usize;
+    buffer[0..2].copy_from_slice(&start.to_be_bytes());
+    colors.iter().enumerate().for_each(|(j, &color)| {
+        let color = color.into().scale(brightness);
+        let pixel = &mut buffer[2 + size * j..2 + size * (j + 1)];
+        match format {
+            PixelFormat::Rgb => {
+                let Rgb { r, g, b } = color.into();
+                pixel.copy_from_slice(&[r, g, b]);
+            }
+            PixelFormat::Rgbw => pixel.copy_from_slice(&[color.r, color.g, color.b, color.w]),
+        }
+    });
+    2 + size * colors.len()
+}
+
+/// NeopixelModule: The color channels of each pixel, which sets how many
+/// bytes of the pixel buffer it takes
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PixelFormat {
+    #[default]
+    Rgb,
+    Rgbw,
+}
+
+impl PixelFormat {
+    pub const fn bytes_per_pixel(self) -> u16 {
+        match self {
+            PixelFormat::Rgb => 3,
+            PixelFormat::Rgbw => 4,
+        }
+    }
+
+    /// The number of bytes `n` pixels take in the pixel buffer
+    pub const fn buffer_len(self, n: u16) -> u16 {
+        self.bytes_per_pixel() * n
+    }
+
+    /// The number of pixels that fit in one `SET_BUF` write, after the 2 byte
+    /// start address
+    const fn pixels_per_write(self) -> usize {
+        (MAX_WRITE_LEN - 2) / self.bytes_per_pixel() as usize
+    }
+
+    /// The byte offset of the `i`th chunk of pixels in the pixel buffer
+    fn chunk_start(self, i: usize) -> u16 {
+        (i * self.pixels_per_write() * self.bytes_per_pixel() as usize) as u16
+    }
+}
+
+/// NeopixelModule: What a device built with `seesaw_device!` keeps for its
+/// NeoPixels. Devices without a NeoPixel module keep `()` instead.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct NeopixelState {
+    pub brightness: u8,
+}
+
+impl Default for NeopixelState {
+    fn default() -> Self {
+        Self { brightness: 255 }
     }
 }
 
 /// NeopixelModule: The Neopixel protocol speed
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum NeopixelSpeed {
     Khz400 = 0,
     #[default]
     Khz800 = 1,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{fill_buffer, NeopixelModule, PixelFormat, MAX_WRITE_LEN};
+    use crate::{
+        devices::NeoKey1x4,
+        mock::{write, MockDriver},
+        Rgbw, SeesawDevice, SeesawError,
+    };
+
+    const ADDR: u8 = 0x30;
+
+    mod rgbw_strip {
+        // Only the NeoPixel module of the device is used
+        #![allow(dead_code)]
+        use crate::{modules::neopixel::PixelFormat, seesaw_device, HardwareId};
+
+        seesaw_device! {
+            /// A strip of RGBW pixels on a seesaw breakout
+            name: RgbwStrip,
+            hardware_id: HardwareId::SAMD09,
+            product_id: 3657,
+            default_addr: 0x49,
+            modules: [
+                NeopixelModule { num_leds: 8, pin: 15, format: PixelFormat::Rgbw },
+            ]
+        }
+    }
+    use rgbw_strip::RgbwStrip;
+
+    #[test]
+    fn enable_neopixel() {
+        let mut dev = NeoKey1x4::new(
+            ADDR,
+            MockDriver::new(&[
+                write(ADDR, [0x0E, 0x01], &[3]),
+                write(ADDR, [0x0E, 0x03], &[0, 12]),
+            ]),
+        );
+        dev.enable_neopixel().unwrap();
+        dev.driver().done();
+    }
+
+    #[test]
+    fn set_nth_neopixel_color() {
+        let mut dev = NeoKey1x4::new(
+            ADDR,
+            MockDriver::new(&[write(ADDR, [0x0E, 0x04], &[0, 9, 0x11, 0x22, 0x33])]),
+        );
+        dev.set_nth_neopixel_color(3, 0x11, 0x22, 0x33).unwrap();
+        dev.driver().done();
+    }
+
+    #[test]
+    fn set_nth_neopixel_color_out_of_range() {
+        let mut dev = NeoKey1x4::new(ADDR, MockDriver::new(&[]));
+        assert!(matches!(
+            dev.set_nth_neopixel_color(4, 0, 0, 0),
+            Err(SeesawError::PixelOutOfRange { index: 4, len: 4 })
+        ));
+        assert!(matches!(
+            dev.set_neopixel_colors(&[(0, 0, 0); 5]),
+            Err(SeesawError::PixelOutOfRange { index: 4, len: 4 })
+        ));
+        dev.driver().done();
+    }
+
+    #[test]
+    fn set_neopixel_colors_in_one_write() {
+        let mut dev = NeoKey1x4::new(
+            ADDR,
+            MockDriver::new(&[write(
+                ADDR,
+                [0x0E, 0x04],
+                &[0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+            )]),
+        );
+        dev.set_neopixel_colors(&[(1, 2, 3), (4, 5, 6), (7, 8, 9)])
+            .unwrap();
+        dev.driver().done();
+    }
+
+    #[test]
+    fn fill_buffer_chunks() {
+        let mut buffer = [0u8; MAX_WRITE_LEN];
+        let format = PixelFormat::Rgb;
+        let colors = [(1, 2, 3); 10];
+        assert_eq!(
+            fill_buffer(&mut buffer, 0, &colors, format, 255),
+            MAX_WRITE_LEN
+        );
+        assert_eq!(&buffer[..5], &[0, 0, 1, 2, 3]);
+        assert_eq!(
+            fill_buffer(
+                &mut buffer,
+                format.chunk_start(1),
+                &colors[..1],
+                format,
+                255
+            ),
+            5
+        );
+        assert_eq!(&buffer[..5], &[0, 30, 1, 2, 3]);
+    }
+
+    #[test]
+    fn rgbw_pixels_take_four_bytes() {
+        let mut dev = RgbwStrip::new(
+            ADDR,
+            MockDriver::new(&[
+                write(ADDR, [0x0E, 0x01], &[15]),
+                write(ADDR, [0x0E, 0x03], &[0, 32]),
+                write(ADDR, [0x0E, 0x04], &[0, 8, 1, 2, 3, 4]),
+                write(ADDR, [0x0E, 0x04], &[0, 4, 0x7F, 0, 0, 0x7F]),
+                write(
+                    ADDR,
+                    [0x0E, 0x04],
+                    &[[0, 0].as_slice(), &[5, 6, 7, 0].repeat(7)].concat(),
+                ),
+                write(ADDR, [0x0E, 0x04], &[0, 28, 5, 6, 7, 0]),
+            ]),
+        );
+        dev.enable_neopixel().unwrap();
+        dev.set_nth_neopixel_rgbw(2, Rgbw::new(1, 2, 3, 4)).unwrap();
+        dev.set_neopixel_brightness(127);
+        dev.set_nth_neopixel_rgbw(1, Rgbw::new(255, 0, 0, 255))
+            .unwrap();
+        dev.set_neopixel_brightness(255);
+        dev.set_neopixel_colors(&[(5, 6, 7); 8]).unwrap();
+        dev.driver().done();
+    }
+
+    #[test]
+    fn rgbw_on_rgb_pixels_mixes_in_the_white() {
+        let mut dev = NeoKey1x4::new(
+            ADDR,
+            MockDriver::new(&[write(ADDR, [0x0E, 0x04], &[0, 3, 0x30, 0x20, 0x20])]),
+        );
+        dev.set_nth_neopixel_rgbw(1, Rgbw::new(0x10, 0, 0, 0x20))
+            .unwrap();
+        dev.driver().done();
+    }
+
+    #[test]
+    fn sync_neopixel() {
+        let mut dev = NeoKey1x4::new(ADDR, MockDriver::new(&[write(ADDR, [0x0E, 0x05], &[])]));
+        dev.sync_neopixel().unwrap();
+        dev.driver().done();
+    }
+}
@@ -1,44 +1,47 @@
+#[cfg(feature = "async")]
+use crate::DriverExtAsync;
 use crate::{driver::Driver, DriverExt, Modules, Reg, SeesawDevice};
 
-const STATUS_HW_ID: &Reg = &[Modules::Status.into_u8(), 0x01];
-const STATUS_VERSION: &Reg = &[Modules::Status.into_u8(), 0x02];
-const STATUS_OPTIONS: &Reg = &[Modules::Status.into_u8(), 0x03];
-const STATUS_TEMP: &Reg = &[Modules::Status.into_u8(), 0x04];
-const STATUS_SWRST: &Reg = &[Modules::Status.into_u8(), 0x7F];
+pub(crate) const STATUS_HW_ID: &Reg = &[Modules::Status.into_u8(), 0x01];
+pub(crate) const STATUS_VERSION: &Reg = &[Modules::Status.into_u8(), 0x02];
+pub(crate) const STATUS_OPTIONS: &Reg = &[Modules::Status.into_u8(), 0x03];
+pub(crate) const STATUS_TEMP: &Reg = &[Modules::Status.into_u8(), 0x04];
+pub(crate) const STATUS_SWRST: &Reg = &[Modules::Status.into_u8(), 0x7F];
 
 pub trait StatusModule<D: Driver>: SeesawDevice<Driver = D> {
     fn capabilities(&mut self) -> Result<DeviceCapabilities, crate::SeesawError<D::I2cError>> {
         let addr = self.addr();
 
-        self.driver()
+        self.bus()
             .read_u32(addr, STATUS_OPTIONS)
             .map(|opts| opts.into())
-            .map_err(crate::SeesawError::I2c)
+            .map_err(crate::SeesawError::i2c(STATUS_OPTIONS))
     }
 
     fn hardware_id(&mut self) -> Result<u8, crate::SeesawError<D::I2cError>> {
         let addr = self.addr();
-        self.driver()
+        self.bus()
             .read_u8(addr, STATUS_HW_ID)
-            .map_err(crate::SeesawError::I2c)
+            .map_err(crate::SeesawError::i2c(STATUS_HW_ID))
     }
 
     fn product_info(&mut self) -> Result<ProductDateCode, crate::SeesawError<D::I2cError>> {
         let addr = self.addr();
 
-        self.driver()
+        self.bus()
             .read_u32(addr, STATUS_VERSION)
             .map(|version| version.into())
-            .map_err(crate::SeesawError::I2c)
+            .map_err(crate::SeesawError::i2c(STATUS_VERSION))
     }
 
     fn reset(&mut self) -> Result<(), crate::SeesawError<D::I2cError>> {
+        let delay = self.timing().reset_delay_us;
         let addr = self.addr();
 
-        self.driver()
+        self.bus()
             .write_u8(addr, STATUS_SWRST, 0xFF)
-            .map(|_| self.driver().delay_us(125_000))
-            .map_err(crate::SeesawError::I2c)
+            .map(|_| self.driver().delay_us(delay))
+            .map_err(crate::SeesawError::i2c(STATUS_SWRST))
     }
 
     fn reset_and_verify_seesaw(&mut self) -> Result<(), crate::SeesawError<D::I2cError>> {
@@ -50,18 +53,105 @@ pub trait StatusModule<D: Driver>: SeesawDevice<Driver = D> {
         })
     }
 
+    /// Check that the device reports the product ID it was defined with. A
+    /// `PRODUCT_ID` of 0, as on
+    /// [`GenericDevice`](crate::devices::GenericDevice), matches any
+    /// product.
+    fn verify_product_id(&mut self) -> Result<(), crate::SeesawError<D::I2cError>> {
+        let expected = Self::PRODUCT_ID;
+        match self.product_info()?.id {
+            found if expected == 0 || found == expected => Ok(()),
+            found => Err(crate::SeesawError::WrongProductId { expected, found }),
+        }
+    }
+
     fn temp(&mut self) -> Result<f32, crate::SeesawError<D::I2cError>> {
         let addr = self.addr();
 
-        self.driver()
+        self.bus()
             .read_u32(addr, STATUS_TEMP)
-            .map(|buf| (buf as f32 / (1u32 << 16) as f32))
-            .map_err(crate::SeesawError::I2c)
+            .map(|buf| buf as f32 / (1u32 << 16) as f32)
+            .map_err(crate::SeesawError::i2c(STATUS_TEMP))
+    }
+}
+
+#[cfg(feature = "async")]
+pub trait StatusModuleAsync<D: crate::DriverAsync>: crate::SeesawDeviceAsync<Driver = D> {
+    async fn capabilities(&mut self) -> Result<DeviceCapabilities, crate::SeesawError<D::Error>> {
+        let addr = self.addr();
+
+        self.bus()
+            .read_u32(addr, STATUS_OPTIONS)
+            .await
+            .map(|opts| opts.into())
+            .map_err(crate::SeesawError::i2c(STATUS_OPTIONS))
+    }
+
+    async fn hardware_id(&mut self) -> Result<u8, crate::SeesawError<D::Error>> {
+        let addr = self.addr();
+        self.bus()
+            .read_u8(addr, STATUS_HW_ID)
+            .await
+            .map_err(crate::SeesawError::i2c(STATUS_HW_ID))
+    }
+
+    async fn product_info(&mut self) -> Result<ProductDateCode, crate::SeesawError<D::Error>> {
+        let addr = self.addr();
+
+        self.bus()
+            .read_u32(addr, STATUS_VERSION)
+            .await
+            .map(|version| version.into())
+            .map_err(crate::SeesawError::i2c(STATUS_VERSION))
+    }
+
+    async fn reset(&mut self) -> Result<(), crate::SeesawError<D::Error>> {
+        let delay = self.timing().reset_delay_us;
+        let addr = self.addr();
+
+        self.bus()
+            .write_u8(addr, STATUS_SWRST, 0xFF)
+            .await
+            .map_err(crate::SeesawError::i2c(STATUS_SWRST))?;
+        self.driver().delay_us(delay).await;
+        Ok(())
+    }
+
+    async fn reset_and_verify_seesaw(&mut self) -> Result<(), crate::SeesawError<D::Error>> {
+        let hw_id = Self::HARDWARE_ID;
+        self.reset().await?;
+        match self.hardware_id().await? {
+            id if id == hw_id.into() => Ok(()),
+            id => Err(crate::SeesawError::InvalidHardwareId(id)),
+        }
+    }
+
+    /// Check that the device reports the product ID it was defined with. A
+    /// `PRODUCT_ID` of 0, as on
+    /// [`GenericDevice`](crate::devices::GenericDevice), matches any
+    /// product.
+    async fn verify_product_id(&mut self) -> Result<(), crate::SeesawError<D::Error>> {
+        let expected = Self::PRODUCT_ID;
+        match self.product_info().await?.id {
+            found if expected == 0 || found == expected => Ok(()),
+            found => Err(crate::SeesawError::WrongProductId { expected, found }),
+        }
+    }
+
+    async fn temp(&mut self) -> Result<f32, crate::SeesawError<D::Error>> {
+        let addr = self.addr();
+
+        self.bus()
+            .read_u32(addr, STATUS_TEMP)
+            .await
+            .map(|buf| buf as f32 / (1u32 << 16) as f32)
+            .map_err(crate::SeesawError::i2c(STATUS_TEMP))
     }
 }
 
 /// StatusModule
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct DeviceCapabilities {
     pub adc: bool,
     pub dac: bool,
@@ -101,7 +191,8 @@ impl From<u32> for DeviceCapabilities {
 }
 
 /// StatusModule
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct ProductDateCode {
     pub id: u16,
     pub year: u16,
@@ -119,3 +210,89 @@ impl From<u32> for ProductDateCode {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        devices::{GenericDevice, RotaryEncoder},
+        mock::{read, write, MockDriver},
+        SeesawError,
+    };
+
+    const ADDR: u8 = 0x49;
+
+    #[test]
+    fn reset_and_verify_seesaw() {
+        let expectations = [
+            vec![write(ADDR, [0x00, 0x7F], &[0xFF])],
+            read(ADDR, [0x00, 0x01], &[0x55]).to_vec(),
+        ]
+        .concat();
+        let mut dev = GenericDevice::new(ADDR, MockDriver::new(&expectations));
+        dev.reset_and_verify_seesaw().unwrap();
+        dev.driver().done();
+    }
+
+    #[test]
+    fn reset_and_verify_seesaw_wrong_id() {
+        let expectations = [
+            vec![write(ADDR, [0x00, 0x7F], &[0xFF])],
+            read(ADDR, [0x00, 0x01], &[0x87]).to_vec(),
+        ]
+        .concat();
+        let mut dev = GenericDevice::new(ADDR, MockDriver::new(&expectations));
+        assert!(matches!(
+            dev.reset_and_verify_seesaw(),
+            Err(SeesawError::InvalidHardwareId(0x87))
+        ));
+        dev.driver().done();
+    }
+
+    #[test]
+    fn verify_product_id() {
+        let expectations = [
+            read(0x36, [0x00, 0x02], &[0x13, 0x7F, 0x00, 0x00]),
+            read(0x36, [0x00, 0x02], &[0x14, 0xAF, 0x00, 0x00]),
+        ]
+        .concat();
+        let mut dev = RotaryEncoder::new(0x36, MockDriver::new(&expectations));
+        dev.verify_product_id().unwrap();
+        assert!(matches!(
+            dev.verify_product_id(),
+            Err(SeesawError::WrongProductId {
+                expected: 4991,
+                found: 5295
+            })
+        ));
+        dev.driver().done();
+    }
+
+    #[test]
+    fn temp() {
+        let mut dev = GenericDevice::new(
+            ADDR,
+            MockDriver::new(&read(ADDR, [0x00, 0x04], &[0x00, 0x1A, 0x40, 0x00])),
+        );
+        assert_eq!(dev.temp().unwrap(), 26.25);
+        dev.driver().done();
+    }
+
+    #[test]
+    fn device_capabilities() {
+        let caps = DeviceCapabilities::from((1 << 0) | (1 << 1) | (1 << 0x0E) | (1 << 0x11));
+        assert!(caps.status && caps.gpio && caps.neopixel && caps.encoder);
+        assert!(!caps.adc && !caps.timer && !caps.touch && !caps.keypad && !caps.sercom0);
+        assert!(!caps.dac && !caps.dap && !caps.eeprom && !caps.interrupt && !caps.spectrum);
+    }
+
+    #[test]
+    fn product_date_code() {
+        // Product 4991, made 2021-12-31
+        let code = ProductDateCode::from((4991 << 16) | (31 << 11) | (12 << 7) | 21);
+        assert_eq!(code.id, 4991);
+        assert_eq!(code.year, 2021);
+        assert_eq!(code.month, 12);
+        assert_eq!(code.day, 31);
+    }
+}
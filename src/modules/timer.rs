@@ -1,32 +1,177 @@
+#[cfg(feature = "async")]
+use crate::DriverExtAsync;
 use crate::{
     common::{Modules, Reg},
-    DriverExt, HardwareId,
+    pins::PwmChannel,
+    DriverExt, HardwareId, PinCapabilities,
 };
 
 /// WO - 16 bits
 /// The first byte of the register indicates which PWM pin will have its value
 /// set The second byte is the actual PWM value
-const PWM_VAL: &Reg = &[Modules::Timer.into_u8(), 0x01];
+pub(crate) const PWM_VAL: &Reg = &[Modules::Timer.into_u8(), 0x01];
 
 /// The PWM module provides up to 4 8-bit PWM outputs.
 /// The module base register address for the PWM module is 0x08.
 /// PWM outputs are available on pins PA04, PA05, PA06, and PA07.
 pub trait TimerModule<D: crate::Driver>: crate::SeesawDevice<Driver = D> {
     fn analog_write(&mut self, pin: u8, value: u8) -> Result<(), crate::SeesawError<D::I2cError>> {
-        let mapped_pin = match Self::HARDWARE_ID {
-            HardwareId::ATTINY817 => pin,
-            HardwareId::SAMD09 => match pin {
-                4 => 0,
-                5 => 1,
-                6 => 2,
-                7 => 3,
-                _ => 0,
-            },
-        };
+        let mapped_pin = pwm_channel(Self::HARDWARE_ID, pin)?;
 
         let addr = self.addr();
-        self.driver()
+        self.bus()
             .write_u16(addr, PWM_VAL, u16::from_be_bytes([mapped_pin, value]))
-            .map_err(crate::SeesawError::I2c)
+            .map_err(crate::SeesawError::i2c(PWM_VAL))
+    }
+
+    /// A handle for the PWM output on `pin` that implements `embedded-hal`'s
+    /// `PwmPin`. Like
+    /// [`GpioModule::split`](crate::modules::gpio::GpioModule::split),
+    /// the handle gets a clone of the driver.
+    fn pwm_pin(&mut self, pin: u8) -> Result<PwmChannel<D>, crate::SeesawError<D::I2cError>>
+    where
+        D: Clone,
+    {
+        let channel = pwm_channel(Self::HARDWARE_ID, pin)?;
+        let addr = self.addr();
+        let timing = *self.timing();
+        Ok(PwmChannel::new(
+            addr,
+            self.driver().clone(),
+            timing,
+            channel,
+        ))
+    }
+}
+
+#[cfg(feature = "async")]
+pub trait TimerModuleAsync<D: crate::DriverAsync>: crate::SeesawDeviceAsync<Driver = D> {
+    async fn analog_write(
+        &mut self,
+        pin: u8,
+        value: u8,
+    ) -> Result<(), crate::SeesawError<D::Error>> {
+        let mapped_pin = pwm_channel(Self::HARDWARE_ID, pin)?;
+
+        let addr = self.addr();
+        self.bus()
+            .write_u16(addr, PWM_VAL, u16::from_be_bytes([mapped_pin, value]))
+            .await
+            .map_err(crate::SeesawError::i2c(PWM_VAL))
+    }
+}
+
+/// The PWM channel that drives `pin`
+pub(crate) fn pwm_channel<E>(
+    hardware_id: HardwareId,
+    pin: u8,
+) -> Result<u8, crate::SeesawError<E>> {
+    PinCapabilities::for_hardware(hardware_id)
+        .pwm_channel(pin)
+        .ok_or(crate::SeesawError::InvalidPin {
+            module: Modules::Timer,
+            pin,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        devices::ArcadeButton1x4,
+        mock::{write, MockDriver},
+        SeesawDevice, SeesawError,
+    };
+    use embedded_hal::PwmPin;
+    use embedded_hal_mock::eh0::MockError;
+    use std::io::ErrorKind;
+
+    #[test]
+    fn analog_write() {
+        let mut dev = ArcadeButton1x4::new(
+            0x3A,
+            MockDriver::new(&[write(0x3A, [0x08, 0x01], &[12, 0x80])]),
+        );
+        dev.analog_write(12, 0x80).unwrap();
+        dev.driver().done();
+    }
+
+    #[test]
+    fn samd09_pwm_channels() {
+        let channel = |hardware_id, pin| pwm_channel::<()>(hardware_id, pin);
+        assert_eq!(
+            [4, 5, 6, 7].map(|pin| channel(HardwareId::SAMD09, pin).unwrap()),
+            [0, 1, 2, 3]
+        );
+        assert_eq!(channel(HardwareId::ATTINY817, 13).unwrap(), 13);
+        assert!(channel(HardwareId::ATTINY817, 14).is_err());
+        assert!(matches!(
+            channel(HardwareId::SAMD09, 2),
+            Err(crate::SeesawError::InvalidPin {
+                module: Modules::Timer,
+                pin: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn pwm_pin_duty() {
+        let mut dev = ArcadeButton1x4::new(
+            0x3A,
+            MockDriver::new(&[
+                write(0x3A, [0x08, 0x01], &[12, 0x40]),
+                write(0x3A, [0x08, 0x01], &[12, 0x80]),
+                write(0x3A, [0x08, 0x01], &[12, 0]),
+                write(0x3A, [0x08, 0x01], &[12, 0x80]),
+            ]),
+        );
+        let mut pwm = dev.pwm_pin(12).unwrap();
+        assert_eq!(pwm.channel(), 12);
+        pwm.try_set_duty(0x40).unwrap();
+        pwm.try_enable().unwrap();
+        pwm.try_set_duty(0x80).unwrap();
+        pwm.try_disable().unwrap();
+        pwm.try_set_duty(0x80).unwrap();
+        pwm.enable();
+        assert_eq!(pwm.get_duty(), 0x80);
+        dev.driver().done();
+    }
+
+    #[test]
+    fn pwm_pin_without_channel() {
+        let mut dev = ArcadeButton1x4::new(0x3A, MockDriver::new(&[]));
+        assert!(matches!(
+            dev.pwm_pin(2),
+            Err(SeesawError::InvalidPin {
+                module: Modules::Timer,
+                pin: 2
+            })
+        ));
+        dev.driver().done();
+    }
+
+    #[test]
+    fn pwm_pin_bus_errors() {
+        let failed = |bytes: &[u8]| {
+            write(0x3A, [0x08, 0x01], bytes).with_error(MockError::Io(ErrorKind::Other))
+        };
+        let mut dev = ArcadeButton1x4::new(
+            0x3A,
+            MockDriver::new(&[failed(&[13, 0]), failed(&[13, 0x20]), failed(&[13, 0x30])]),
+        );
+        let mut pwm = dev.pwm_pin(13).unwrap();
+        assert!(matches!(
+            pwm.try_enable(),
+            Err(SeesawError::I2c {
+                reg: [0x08, 0x01],
+                source: MockError::Io(ErrorKind::Other)
+            })
+        ));
+        assert!(pwm.try_set_duty(0x20).is_err());
+        // The embedded-hal methods can't report the error, but still keep the
+        // duty
+        pwm.set_duty(0x30);
+        assert_eq!(pwm.get_duty(), 0x30);
+        dev.driver().done();
     }
 }
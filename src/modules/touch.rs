@@ -0,0 +1,104 @@
+#[cfg(feature = "async")]
+use crate::DriverExtAsync;
+use crate::{
+    common::{Modules, Reg},
+    DriverExt, HardwareId, PinCapabilities,
+};
+
+/// RO - 16 bits
+/// The measurement for touch channel 0. The other channels follow it.
+const CHANNEL_0: &Reg = &[Modules::Touch.into_u8(), 0x10];
+
+/// The touch module measures the capacitance of pins wired to a touch pad,
+/// which goes up when the pad is touched. Only the pins in the chip's
+/// [`PinCapabilities::touch`] table have a touch channel.
+///
+/// The module base register address for the touch module is 0x0F.
+///
+/// `touch_read` waits
+/// [`Timing::touch_delay_us`](crate::Timing::touch_delay_us) between
+/// selecting the channel and reading it.
+pub trait TouchModule<D: crate::Driver>: crate::SeesawDevice<Driver = D> {
+    fn touch_read(&mut self, pin: u8) -> Result<u16, crate::SeesawError<D::I2cError>> {
+        let reg = channel_reg(Self::HARDWARE_ID, pin)?;
+
+        let addr = self.addr();
+        let delay = self.timing().touch_delay_us;
+        self.bus()
+            .with_read_delay(delay)
+            .read_u16(addr, &reg)
+            .map_err(crate::SeesawError::i2c(&reg))
+    }
+}
+
+#[cfg(feature = "async")]
+pub trait TouchModuleAsync<D: crate::DriverAsync>: crate::SeesawDeviceAsync<Driver = D> {
+    async fn touch_read(&mut self, pin: u8) -> Result<u16, crate::SeesawError<D::Error>> {
+        let reg = channel_reg(Self::HARDWARE_ID, pin)?;
+
+        let addr = self.addr();
+        let delay = self.timing().touch_delay_us;
+        self.bus()
+            .with_read_delay(delay)
+            .read_u16(addr, &reg)
+            .await
+            .map_err(crate::SeesawError::i2c(&reg))
+    }
+}
+
+/// The register holding the measurement for `pin`
+fn channel_reg<E>(hardware_id: HardwareId, pin: u8) -> Result<Reg, crate::SeesawError<E>> {
+    PinCapabilities::for_hardware(hardware_id)
+        .touch_channel(pin)
+        .map(|offset| [CHANNEL_0[0], CHANNEL_0[1] + offset])
+        .ok_or(crate::SeesawError::InvalidPin {
+            module: Modules::Touch,
+            pin,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mock::{read, MockDriver},
+        SeesawDevice,
+    };
+
+    #[test]
+    fn samd09_touch_channel() {
+        let mut dev = samd09::TouchDevice::new(
+            0x36,
+            MockDriver::new(&read(0x36, [0x0F, 0x10], &[0x03, 0x20])),
+        );
+        assert_eq!(dev.touch_read(7).unwrap(), 800);
+        dev.driver().done();
+    }
+
+    #[test]
+    fn pins_without_a_touch_channel() {
+        let mut dev = samd09::TouchDevice::new(0x36, MockDriver::new(&[]));
+        assert!(matches!(
+            dev.touch_read(6),
+            Err(crate::SeesawError::InvalidPin {
+                module: Modules::Touch,
+                pin: 6
+            })
+        ));
+        assert!(channel_reg::<()>(HardwareId::ATTINY817, 7).is_err());
+        dev.driver().done();
+    }
+
+    #[allow(dead_code)]
+    mod samd09 {
+        use crate::HardwareId;
+
+        crate::seesaw_device! {
+            name: TouchDevice,
+            hardware_id: HardwareId::SAMD09,
+            product_id: 0,
+            default_addr: 0x36,
+            modules: [TouchModule]
+        }
+    }
+}
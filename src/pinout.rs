@@ -0,0 +1,100 @@
+use crate::common::HardwareId;
+
+/// What each pin of a seesaw chip can do, by seesaw pin number.
+///
+/// The channel tables pair a pin with the channel the firmware uses for it,
+/// which is what ends up in the ADC, PWM and touch registers:
+///
+/// ```
+/// # use adafruit_seesaw::{HardwareId, PinCapabilities};
+/// let pins = PinCapabilities::for_hardware(HardwareId::SAMD09);
+/// assert_eq!(pins.adc_channel(3), Some(1));
+/// assert_eq!(pins.pwm_channel(3), None);
+/// assert_eq!(pins.touch_channel(7), Some(0));
+/// assert!(!pins.is_digital(12));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PinCapabilities {
+    /// Bitmask of the pins the GPIO module can read and drive
+    pub digital: u32,
+    /// Bitmask of the pins whose changes can raise the interrupt line. The
+    /// firmware watches pins for changes in software, so this is every GPIO
+    /// pin on both chips.
+    pub interrupt: u32,
+    /// `(pin, channel)` pairs for the ADC module
+    pub adc: &'static [(u8, u8)],
+    /// `(pin, channel)` pairs for the timer module
+    pub pwm: &'static [(u8, u8)],
+    /// `(pin, channel)` pairs for the touch module
+    pub touch: &'static [(u8, u8)],
+}
+
+impl PinCapabilities {
+    /// The ATtiny8x7 breakout: pins 0 to 20, with the ADC and PWM channel
+    /// numbered after their pin. Its firmware has no touch channels.
+    pub const ATTINY817: Self = Self {
+        digital: 0x001F_FFFF,
+        interrupt: 0x001F_FFFF,
+        adc: &[
+            (0, 0),
+            (1, 1),
+            (2, 2),
+            (3, 3),
+            (6, 6),
+            (7, 7),
+            (18, 18),
+            (19, 19),
+            (20, 20),
+        ],
+        pwm: &[(0, 0), (1, 1), (9, 9), (12, 12), (13, 13)],
+        touch: &[],
+    };
+    /// The SAMD09 breakout: PA02 to PA11, PA14, PA15, PA24 and PA25, with a
+    /// single touch channel on PA07
+    pub const SAMD09: Self = Self {
+        digital: 0x0300_CFFC,
+        interrupt: 0x0300_CFFC,
+        adc: &[(2, 0), (3, 1), (4, 2), (5, 3)],
+        pwm: &[(4, 0), (5, 1), (6, 2), (7, 3)],
+        touch: &[(7, 0)],
+    };
+
+    pub const fn for_hardware(hardware_id: HardwareId) -> Self {
+        match hardware_id {
+            HardwareId::ATTINY817 => Self::ATTINY817,
+            HardwareId::SAMD09 => Self::SAMD09,
+        }
+    }
+
+    pub const fn is_digital(&self, pin: u8) -> bool {
+        pin < 32 && self.digital >> pin & 1 == 1
+    }
+
+    pub const fn is_interrupt(&self, pin: u8) -> bool {
+        pin < 32 && self.interrupt >> pin & 1 == 1
+    }
+
+    pub const fn adc_channel(&self, pin: u8) -> Option<u8> {
+        channel(self.adc, pin)
+    }
+
+    pub const fn pwm_channel(&self, pin: u8) -> Option<u8> {
+        channel(self.pwm, pin)
+    }
+
+    pub const fn touch_channel(&self, pin: u8) -> Option<u8> {
+        channel(self.touch, pin)
+    }
+}
+
+const fn channel(table: &[(u8, u8)], pin: u8) -> Option<u8> {
+    let mut i = 0;
+    while i < table.len() {
+        if table[i].0 == pin {
+            return Some(table[i].1);
+        }
+        i += 1;
+    }
+    None
+}
@@ -0,0 +1,437 @@
+//! Handles for single pins that implement the `embedded-hal` pin traits, so a
+//! seesaw's pins can be passed to drivers written against those traits.
+//!
+//! [`GpioModule::split`](crate::modules::gpio::GpioModule::split) hands out a
+//! [`Pin`] for each of a device's GPIO pins. Every handle holds its own clone
+//! of the device's driver, so the driver needs to be a cheap, shared one such
+//! as a [`BusProxy`](crate::bus::BusProxy). A pin's mode lives in its type, and
+//! changes by consuming the handle:
+//!
+//! ```ignore
+//! let pins = neokeys.split();
+//! let mut led = pins.p4.into_push_pull_output()?;
+//! let button = pins.p5.into_pull_up_input()?;
+//! if button.is_low()? {
+//!     led.set_high()?;
+//! }
+//! ```
+//!
+//! [`TimerModule::pwm_pin`](crate::modules::timer::TimerModule::pwm_pin) hands
+//! out a [`PwmChannel`] for a PWM-capable pin in the same way. Devices with an
+//! ADC implement `OneShot` themselves, sampling the [`AdcPin`]s from
+//! [`AdcModule::adc_pin`](crate::modules::adc::AdcModule::adc_pin).
+//!
+//! Levels are reported as they are on the wire. This differs from
+//! [`GpioModule::digital_read`](crate::modules::gpio::GpioModule::digital_read),
+//! which reports a pin pulled low as `true`.
+use crate::{
+    driver::{Driver, DriverExt, TimedDriver},
+    modules::{
+        gpio::{pin_mask, write_pin_mode, PinMode, GPIO, SET_HIGH, SET_LOW, TOGGLE},
+        timer::PWM_VAL,
+    },
+    HardwareId, PinCapabilities, Reg, SeesawDevice, SeesawError, Timing,
+};
+use core::marker::PhantomData;
+use embedded_hal::{
+    adc::Channel,
+    digital::v2::{InputPin, OutputPin, ToggleableOutputPin},
+    PwmPin,
+};
+// For the `OneShot` implementations generated by `seesaw_device!`
+#[doc(hidden)]
+pub use embedded_hal::adc::OneShot;
+#[doc(hidden)]
+pub use nb;
+
+/// Input mode, with the pull given by `PULL`
+#[derive(Debug)]
+pub struct Input<PULL>(PhantomData<PULL>);
+
+/// The pin's internal pullup is enabled
+#[derive(Debug)]
+pub struct PullUp;
+
+/// The pin's internal pulldown is enabled
+#[derive(Debug)]
+pub struct PullDown;
+
+/// Neither pull is enabled. Pins start out this way after a reset.
+#[derive(Debug)]
+pub struct Floating;
+
+/// The mode of a pin fresh from
+/// [`GpioModule::split`](crate::modules::gpio::GpioModule::split). The pin may
+/// not exist on the chip, and `init` may have set it to anything, so it can't
+/// be read or written until one of the `into_*` methods sets its mode:
+///
+/// ```compile_fail
+/// # use adafruit_seesaw::{prelude::*, sim::{SimDelay, SimDevice}, SeesawSingleThread};
+/// # use embedded_hal::digital::v2::InputPin;
+/// let sim = SimDevice::new(0x30, NeoKey1x4::hardware_id());
+/// let seesaw = SeesawSingleThread::new(SimDelay::default(), sim);
+/// let mut neokeys = NeoKey1x4::new(0x30, seesaw.acquire_driver());
+/// neokeys.split().p4.is_high();
+/// ```
+#[derive(Debug)]
+pub struct Unknown;
+
+/// Push-pull output mode
+#[derive(Debug)]
+pub struct Output;
+
+/// GPIO pin `N` of a device, in mode `MODE`. Pins the device's chip doesn't
+/// have fail to change mode with [`SeesawError::InvalidPin`].
+#[derive(Debug)]
+pub struct Pin<const N: u8, MODE, D> {
+    addr: u8,
+    driver: D,
+    timing: Timing,
+    hardware_id: HardwareId,
+    _mode: PhantomData<MODE>,
+}
+
+impl<const N: u8, MODE, D: Driver> Pin<N, MODE, D> {
+    pub(crate) fn new(addr: u8, driver: D, timing: Timing, hardware_id: HardwareId) -> Self {
+        Self {
+            addr,
+            driver,
+            timing,
+            hardware_id,
+            _mode: PhantomData,
+        }
+    }
+
+    /// The number of the pin on its device
+    pub fn pin(&self) -> u8 {
+        N
+    }
+
+    pub fn into_push_pull_output(self) -> Result<Pin<N, Output, D>, SeesawError<D::I2cError>> {
+        self.into_mode(PinMode::Output)
+    }
+
+    pub fn into_floating_input(
+        self,
+    ) -> Result<Pin<N, Input<Floating>, D>, SeesawError<D::I2cError>> {
+        self.into_mode(PinMode::Input)
+    }
+
+    pub fn into_pull_up_input(self) -> Result<Pin<N, Input<PullUp>, D>, SeesawError<D::I2cError>> {
+        self.into_mode(PinMode::InputPullup)
+    }
+
+    pub fn into_pull_down_input(
+        self,
+    ) -> Result<Pin<N, Input<PullDown>, D>, SeesawError<D::I2cError>> {
+        self.into_mode(PinMode::InputPulldown)
+    }
+
+    fn into_mode<M>(mut self, mode: PinMode) -> Result<Pin<N, M, D>, SeesawError<D::I2cError>> {
+        let mask = pin_mask(self.hardware_id, N)?;
+        let addr = self.addr;
+        write_pin_mode(&mut self.bus(), addr, mask, mode)?;
+        Ok(Pin::new(
+            self.addr,
+            self.driver,
+            self.timing,
+            self.hardware_id,
+        ))
+    }
+
+    fn bus(&mut self) -> TimedDriver<'_, D> {
+        TimedDriver::new(&mut self.driver, &self.timing)
+    }
+
+    fn write(&mut self, reg: &'static Reg) -> Result<(), SeesawError<D::I2cError>> {
+        let addr = self.addr;
+        self.bus()
+            .write_u32(addr, reg, 1 << N)
+            .map_err(SeesawError::i2c(reg))
+    }
+}
+
+impl<const N: u8, D: Driver> OutputPin for Pin<N, Output, D> {
+    type Error = SeesawError<D::I2cError>;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.write(SET_LOW)
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.write(SET_HIGH)
+    }
+}
+
+impl<const N: u8, D: Driver> ToggleableOutputPin for Pin<N, Output, D> {
+    type Error = SeesawError<D::I2cError>;
+
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        self.write(TOGGLE)
+    }
+}
+
+/// Reading takes `&self`, so each read goes through a fresh clone of the
+/// driver
+impl<const N: u8, PULL, D: Driver + Clone> InputPin for Pin<N, Input<PULL>, D> {
+    type Error = SeesawError<D::I2cError>;
+
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        let mut driver = self.driver.clone();
+        TimedDriver::new(&mut driver, &self.timing)
+            .read_u32(self.addr, GPIO)
+            .map(|pins| pins >> N & 0x1 == 1)
+            .map_err(SeesawError::i2c(GPIO))
+    }
+
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        self.is_high().map(|high| !high)
+    }
+}
+
+macro_rules! pins {
+    ($($field:ident: $n:literal),* $(,)?) => {
+        /// A handle for every GPIO pin a seesaw can address, as returned by
+        /// [`GpioModule::split`](crate::modules::gpio::GpioModule::split).
+        /// Which of them exist depends on the chip, and which are broken out
+        /// on the board. Every pin starts in the [`Unknown`] mode.
+        #[derive(Debug)]
+        pub struct Pins<D> {
+            $(pub $field: Pin<$n, Unknown, D>,)*
+        }
+
+        impl<D: Driver + Clone> Pins<D> {
+            pub(crate) fn new(
+                addr: u8,
+                driver: &D,
+                timing: Timing,
+                hardware_id: HardwareId,
+            ) -> Self {
+                Self {
+                    $($field: Pin::new(addr, driver.clone(), timing, hardware_id),)*
+                }
+            }
+        }
+    };
+}
+
+pins!(
+    p0: 0, p1: 1, p2: 2, p3: 3, p4: 4, p5: 5, p6: 6, p7: 7,
+    p8: 8, p9: 9, p10: 10, p11: 11, p12: 12, p13: 13, p14: 14, p15: 15,
+    p16: 16, p17: 17, p18: 18, p19: 19, p20: 20, p21: 21, p22: 22, p23: 23,
+    p24: 24, p25: 25, p26: 26, p27: 27, p28: 28, p29: 29, p30: 30, p31: 31,
+);
+
+/// One of a device's 8-bit PWM outputs.
+///
+/// `PwmPin`'s methods can't fail, so they drop bus errors. The `try_` methods
+/// do the same things and report them.
+#[derive(Debug)]
+pub struct PwmChannel<D> {
+    addr: u8,
+    driver: D,
+    timing: Timing,
+    channel: u8,
+    duty: u8,
+    enabled: bool,
+}
+
+impl<D: Driver> PwmChannel<D> {
+    /// A disabled channel with a duty of 0
+    pub(crate) fn new(addr: u8, driver: D, timing: Timing, channel: u8) -> Self {
+        Self {
+            addr,
+            driver,
+            timing,
+            channel,
+            duty: 0,
+            enabled: false,
+        }
+    }
+
+    /// The timer channel, which on SAMD09 boards differs from the pin number
+    pub fn channel(&self) -> u8 {
+        self.channel
+    }
+
+    /// Start outputting the current duty
+    pub fn try_enable(&mut self) -> Result<(), SeesawError<D::I2cError>> {
+        self.enabled = true;
+        self.write(self.duty)
+    }
+
+    /// Hold the output low, keeping the duty for when it's enabled again
+    pub fn try_disable(&mut self) -> Result<(), SeesawError<D::I2cError>> {
+        self.enabled = false;
+        self.write(0)
+    }
+
+    /// Set the duty, which is only written out while the channel is enabled
+    pub fn try_set_duty(&mut self, duty: u8) -> Result<(), SeesawError<D::I2cError>> {
+        self.duty = duty;
+        if self.enabled {
+            self.write(duty)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn write(&mut self, value: u8) -> Result<(), SeesawError<D::I2cError>> {
+        TimedDriver::new(&mut self.driver, &self.timing)
+            .write_u16(
+                self.addr,
+                PWM_VAL,
+                u16::from_be_bytes([self.channel, value]),
+            )
+            .map_err(SeesawError::i2c(PWM_VAL))
+    }
+}
+
+impl<D: Driver> PwmPin for PwmChannel<D> {
+    type Duty = u8;
+
+    fn disable(&mut self) {
+        let _ = self.try_disable();
+    }
+
+    fn enable(&mut self) {
+        let _ = self.try_enable();
+    }
+
+    fn get_duty(&self) -> u8 {
+        self.duty
+    }
+
+    fn get_max_duty(&self) -> u8 {
+        u8::MAX
+    }
+
+    fn set_duty(&mut self, duty: u8) {
+        let _ = self.try_set_duty(duty);
+    }
+}
+
+/// Analog input pin `N` of a device of type `DEV`, which the device samples
+/// through `embedded-hal`'s `OneShot`. It can only be created for pins with an
+/// ADC channel on the device's chip:
+///
+/// ```compile_fail
+/// # use adafruit_seesaw::{pins::AdcPin, sim::SimDevice, RotaryEncoder};
+/// // The SAMD09 only has ADC channels on pins 2 to 5
+/// let pin = AdcPin::<RotaryEncoder<SimDevice>, 9>::new();
+/// ```
+pub struct AdcPin<DEV, const N: u8>(PhantomData<DEV>);
+
+impl<DEV: SeesawDevice, const N: u8> AdcPin<DEV, N> {
+    const VALID: () = assert!(
+        PinCapabilities::for_hardware(DEV::HARDWARE_ID)
+            .adc_channel(N)
+            .is_some(),
+        "the pin has no ADC channel on this device"
+    );
+
+    pub fn new() -> Self {
+        let () = Self::VALID;
+        Self(PhantomData)
+    }
+}
+
+impl<DEV: SeesawDevice, const N: u8> Default for AdcPin<DEV, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<DEV, const N: u8> core::fmt::Debug for AdcPin<DEV, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("AdcPin").field(&N).finish()
+    }
+}
+
+/// The channel's ID is the pin number, as taken by
+/// [`AdcModule::analog_read`](crate::modules::adc::AdcModule::analog_read)
+impl<DEV, const N: u8> Channel<DEV> for AdcPin<DEV, N> {
+    type ID = u8;
+
+    fn channel() -> u8 {
+        N
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Pins, PwmChannel};
+    use crate::{
+        mock::{read, write, MockDriver},
+        HardwareId, Modules, SeesawError, Timing,
+    };
+    use embedded_hal::{
+        digital::v2::{InputPin, OutputPin, ToggleableOutputPin},
+        PwmPin,
+    };
+
+    const ADDR: u8 = 0x30;
+
+    #[test]
+    fn output_pin() {
+        let mut bus = MockDriver::new(&[
+            write(ADDR, [0x01, 0x02], &[0, 0, 0, 0b100]),
+            write(ADDR, [0x01, 0x05], &[0, 0, 0, 0b100]),
+            write(ADDR, [0x01, 0x07], &[0, 0, 0, 0b100]),
+        ]);
+        let pins = Pins::new(ADDR, &bus, Timing::DEFAULT, HardwareId::ATTINY817);
+        let mut led = pins.p2.into_push_pull_output().unwrap();
+        led.set_high().unwrap();
+        led.toggle().unwrap();
+        bus.done();
+    }
+
+    #[test]
+    fn pull_up_input_pin() {
+        let [address, value] = read(ADDR, [0x01, 0x04], &[0, 0, 0, 0b1000]);
+        let mut bus = MockDriver::new(&[
+            write(ADDR, [0x01, 0x03], &[0, 0, 0, 0b1000]),
+            write(ADDR, [0x01, 0x0B], &[0, 0, 0, 0b1000]),
+            write(ADDR, [0x01, 0x05], &[0, 0, 0, 0b1000]),
+            address,
+            value,
+        ]);
+        let pins = Pins::new(ADDR, &bus, Timing::DEFAULT, HardwareId::ATTINY817);
+        let button = pins.p3.into_pull_up_input().unwrap();
+        assert!(button.is_high().unwrap());
+        bus.done();
+    }
+
+    #[test]
+    fn pins_missing_from_the_chip() {
+        let mut bus = MockDriver::new(&[]);
+        let pins = Pins::new(ADDR, &bus, Timing::DEFAULT, HardwareId::SAMD09);
+        assert!(matches!(
+            pins.p12.into_push_pull_output(),
+            Err(SeesawError::InvalidPin {
+                module: Modules::Gpio,
+                pin: 12
+            })
+        ));
+        assert!(pins.p0.into_pull_up_input().is_err());
+        bus.done();
+    }
+
+    #[test]
+    fn pwm_channel() {
+        let mut bus = MockDriver::new(&[
+            write(ADDR, [0x08, 0x01], &[2, 0x40]),
+            write(ADDR, [0x08, 0x01], &[2, 0x80]),
+            write(ADDR, [0x08, 0x01], &[2, 0]),
+        ]);
+        let mut pwm = PwmChannel::new(ADDR, bus.clone(), Timing::DEFAULT, 2);
+        pwm.set_duty(0x40);
+        pwm.enable();
+        pwm.set_duty(0x80);
+        pwm.disable();
+        pwm.set_duty(0xFF);
+        assert_eq!(pwm.get_duty(), 0xFF);
+        assert_eq!(pwm.get_max_duty(), 0xFF);
+        bus.done();
+    }
+}
@@ -0,0 +1,270 @@
+//! Retrying I2C transactions that fail while a device is busy.
+//!
+//! Seesaw boards, SAMD09 ones especially, NACK while they are busy, such as
+//! right after a reset or while pushing out NeoPixel data. [`RetryDriver`]
+//! wraps a device's driver and repeats a transaction that fails with an error
+//! its [`Classify`] accepts, waiting between attempts with the driver's own
+//! delay. It counts what it does in [`RetryStats`], for keeping an eye on the
+//! health of the bus.
+//!
+//! Each I2C transaction is retried on its own, so when the address write of a
+//! register read goes through but the read doesn't, only the read is repeated.
+//!
+//! ```ignore
+//! let policy = RetryPolicy {
+//!     max_attempts: 4,
+//!     backoff_us: 500,
+//! };
+//! // Retry every error. With an `embedded-hal` 1.0 bus, `Nacks` retries only
+//! // NACKs.
+//! let driver = RetryDriver::new(seesaw.acquire_driver(), policy, |_: &_| true);
+//! let mut encoder = RotaryEncoder::new_with_default_addr(driver).init()?;
+//!
+//! // Later
+//! rprintln!("{:?}", encoder.driver().stats());
+//! ```
+use crate::driver::Driver;
+use embedded_hal::blocking::{delay, i2c};
+
+/// How often, and how patiently, to retry a transaction
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RetryPolicy {
+    /// How many times to try a transaction, including the first attempt
+    pub max_attempts: u8,
+    /// How long to wait before the first retry, in microseconds. The wait
+    /// doubles before each further retry.
+    pub backoff_us: u32,
+}
+
+impl RetryPolicy {
+    pub const DEFAULT: Self = Self {
+        max_attempts: 3,
+        backoff_us: 1_000,
+    };
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Decides which errors are worth retrying. Implemented for closures that
+/// take the error.
+pub trait Classify<E> {
+    fn is_retryable(&mut self, err: &E) -> bool;
+}
+
+impl<E, F: FnMut(&E) -> bool> Classify<E> for F {
+    fn is_retryable(&mut self, err: &E) -> bool {
+        self(err)
+    }
+}
+
+/// Retries NACKs of the address or of data, and nothing else, for buses that
+/// report `embedded-hal` 1.0 errors such as the
+/// [`eh1::I2c`](crate::eh1::I2c) adapter
+#[cfg(feature = "embedded-hal-1")]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Nacks;
+
+#[cfg(feature = "embedded-hal-1")]
+impl<E: embedded_hal_1::i2c::Error> Classify<E> for Nacks {
+    fn is_retryable(&mut self, err: &E) -> bool {
+        matches!(err.kind(), embedded_hal_1::i2c::ErrorKind::NoAcknowledge(_))
+    }
+}
+
+/// What a [`RetryDriver`] has done since it was created or its stats were
+/// last reset
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RetryStats {
+    /// Attempts made after a first one failed
+    pub retries: u32,
+    /// Transactions that went through after being retried
+    pub recovered: u32,
+    /// Transactions that were retried and still failed
+    pub gave_up: u32,
+}
+
+/// A driver that retries the failed transactions of the driver it wraps
+#[derive(Debug)]
+pub struct RetryDriver<D, C> {
+    driver: D,
+    policy: RetryPolicy,
+    classify: C,
+    stats: RetryStats,
+}
+
+impl<D: Driver, C: Classify<D::I2cError>> RetryDriver<D, C> {
+    pub fn new(driver: D, policy: RetryPolicy, classify: C) -> Self {
+        Self {
+            driver,
+            policy,
+            classify,
+            stats: RetryStats::default(),
+        }
+    }
+
+    pub fn driver(&mut self) -> &mut D {
+        &mut self.driver
+    }
+
+    pub fn policy(&self) -> RetryPolicy {
+        self.policy
+    }
+
+    pub fn stats(&self) -> RetryStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = RetryStats::default();
+    }
+
+    pub fn release(self) -> D {
+        self.driver
+    }
+
+    fn retry<T>(
+        &mut self,
+        mut transaction: impl FnMut(&mut D) -> Result<T, D::I2cError>,
+    ) -> Result<T, D::I2cError> {
+        let mut attempt = 1;
+        let mut backoff = self.policy.backoff_us;
+        loop {
+            match transaction(&mut self.driver) {
+                Ok(value) => {
+                    if attempt > 1 {
+                        self.stats.recovered += 1;
+                    }
+                    return Ok(value);
+                }
+                Err(err)
+                    if attempt < self.policy.max_attempts && self.classify.is_retryable(&err) =>
+                {
+                    self.driver.delay_us(backoff);
+                    backoff = backoff.saturating_mul(2);
+                    attempt += 1;
+                    self.stats.retries += 1;
+                }
+                Err(err) => {
+                    if attempt > 1 {
+                        self.stats.gave_up += 1;
+                    }
+                    return Err(err);
+                }
+            }
+        }
+    }
+}
+
+impl<D: Driver, C: Classify<D::I2cError>> i2c::Write for RetryDriver<D, C> {
+    type Error = D::I2cError;
+
+    fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.retry(|driver| driver.write(addr, bytes).map_err(D::I2cError::from))
+    }
+}
+
+impl<D: Driver, C: Classify<D::I2cError>> i2c::Read for RetryDriver<D, C> {
+    type Error = D::I2cError;
+
+    fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.retry(|driver| driver.read(addr, buffer).map_err(D::I2cError::from))
+    }
+}
+
+impl<D: Driver, C: Classify<D::I2cError>> i2c::WriteRead for RetryDriver<D, C> {
+    type Error = D::I2cError;
+
+    fn write_read(&mut self, addr: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.retry(|driver| {
+            driver
+                .write_read(addr, bytes, buffer)
+                .map_err(D::I2cError::from)
+        })
+    }
+}
+
+impl<D: Driver, C> delay::DelayUs<u32> for RetryDriver<D, C> {
+    fn delay_us(&mut self, us: u32) {
+        self.driver.delay_us(us)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RetryDriver, RetryPolicy, RetryStats};
+    use crate::{
+        devices::NeoKey1x4,
+        mock::{read, write, MockDriver},
+        modules::gpio::GpioModule,
+        SeesawDevice,
+    };
+    use embedded_hal_mock::eh0::MockError;
+
+    const ADDR: u8 = 0x30;
+
+    fn nack() -> MockError {
+        MockError::Io(std::io::ErrorKind::Other)
+    }
+
+    #[test]
+    fn recovers_after_retry() {
+        let bus = MockDriver::new(&[
+            write(ADDR, [0x01, 0x05], &[0, 0, 0, 0x10]).with_error(nack()),
+            write(ADDR, [0x01, 0x05], &[0, 0, 0, 0x10]),
+        ]);
+        let driver = RetryDriver::new(bus, RetryPolicy::DEFAULT, |_: &MockError| true);
+        let mut dev = NeoKey1x4::new(ADDR, driver);
+        dev.digital_write(4, true).unwrap();
+        assert_eq!(
+            dev.driver().stats(),
+            RetryStats {
+                retries: 1,
+                recovered: 1,
+                gave_up: 0
+            }
+        );
+        dev.driver().driver().done();
+    }
+
+    #[test]
+    fn gives_up_after_max_attempts() {
+        let [address, value] = read(ADDR, [0x01, 0x04], &[0, 0, 0, 0]);
+        let policy = RetryPolicy {
+            max_attempts: 2,
+            ..RetryPolicy::DEFAULT
+        };
+        let bus = MockDriver::new(&[
+            address,
+            value.clone().with_error(nack()),
+            value.with_error(nack()),
+        ]);
+        let mut dev = NeoKey1x4::new(ADDR, RetryDriver::new(bus, policy, |_: &MockError| true));
+        assert!(dev.digital_read_bulk().is_err());
+        assert_eq!(
+            dev.driver().stats(),
+            RetryStats {
+                retries: 1,
+                recovered: 0,
+                gave_up: 1
+            }
+        );
+        dev.driver().driver().done();
+    }
+
+    #[test]
+    fn leaves_other_errors_alone() {
+        let bus =
+            MockDriver::new(&[write(ADDR, [0x01, 0x05], &[0, 0, 0, 0x10]).with_error(nack())]);
+        let driver = RetryDriver::new(bus, RetryPolicy::DEFAULT, |_: &MockError| false);
+        let mut dev = NeoKey1x4::new(ADDR, driver);
+        assert!(dev.digital_write(4, true).is_err());
+        assert_eq!(dev.driver().stats(), RetryStats::default());
+        dev.driver().driver().done();
+    }
+}
@@ -0,0 +1,638 @@
+//! An in-memory seesaw device for testing without hardware.
+//!
+//! [`SimDevice`] emulates the register file of a seesaw device's Status, GPIO,
+//! ADC, Timer, Encoder and NeoPixel modules. It implements the same I2C and
+//! delay traits as [`Driver`](crate::Driver), so it can be handed to a device
+//! directly or shared through a [`Seesaw`](crate::Seesaw) like a real bus.
+//!
+//! ```
+//! use adafruit_seesaw::{prelude::*, sim::SimDevice};
+//!
+//! let sim = SimDevice::new(RotaryEncoder::default_addr(), RotaryEncoder::hardware_id())
+//!     .with_product_id(RotaryEncoder::product_id());
+//! let mut encoder = RotaryEncoder::new_with_default_addr(sim).init().unwrap();
+//!
+//! encoder.driver().turn(3);
+//! assert_eq!(encoder.position().unwrap(), 3);
+//! ```
+use crate::{common::Modules, HardwareId, Reg};
+use embedded_hal::blocking::{delay, i2c};
+
+/// Size of the emulated NeoPixel buffer, in bytes
+pub const NEOPIXEL_BUF_LEN: usize = 256;
+
+/// Number of emulated ADC and PWM channels
+pub const N_CHANNELS: usize = 32;
+
+/// Errors reported by the emulated bus
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SimError {
+    /// Nothing answered at the requested address
+    Nack(u8),
+    /// A register that the emulator doesn't implement was accessed
+    UnsupportedRegister(Reg),
+    /// A read was attempted without first writing a register address
+    NoRegisterSelected,
+}
+
+/// An emulated seesaw device
+#[derive(Clone, Debug)]
+pub struct SimDevice {
+    addr: u8,
+    hardware_id: u8,
+    version: u32,
+    options: u32,
+    temp: u32,
+    selected: Option<Reg>,
+    elapsed_us: u64,
+    resets: u32,
+    // GPIO
+    direction: u32,
+    output: u32,
+    pulls: u32,
+    driven: u32,
+    driven_levels: u32,
+    interrupts: u32,
+    interrupt_flags: u32,
+    // ADC
+    adc: [u16; N_CHANNELS],
+    // Timer
+    pwm: [u16; N_CHANNELS],
+    pwm_freq: [u16; N_CHANNELS],
+    // Encoder
+    position: i32,
+    delta: i32,
+    encoder_interrupt: bool,
+    // NeoPixel
+    neopixel_pin: u8,
+    neopixel_speed: u8,
+    neopixel_len: u16,
+    neopixel_buf: [u8; NEOPIXEL_BUF_LEN],
+    neopixel_shown: [u8; NEOPIXEL_BUF_LEN],
+    shows: u32,
+}
+
+impl SimDevice {
+    /// Create a device that answers at `addr` and reports `hardware_id`.
+    /// All modules the emulator implements are reported as capabilities.
+    pub fn new(addr: u8, hardware_id: HardwareId) -> Self {
+        Self::power_on(addr, hardware_id.into())
+    }
+
+    fn power_on(addr: u8, hardware_id: u8) -> Self {
+        Self {
+            addr,
+            hardware_id,
+            version: 0,
+            options: [
+                Modules::Status,
+                Modules::Gpio,
+                Modules::Timer,
+                Modules::Adc,
+                Modules::Neopixel,
+                Modules::Encoder,
+            ]
+            .iter()
+            .fold(0, |opts, &module| opts | 1 << module.into_u8()),
+            temp: 0,
+            selected: None,
+            elapsed_us: 0,
+            resets: 0,
+            direction: 0,
+            output: 0,
+            pulls: 0,
+            driven: 0,
+            driven_levels: 0,
+            interrupts: 0,
+            interrupt_flags: 0,
+            adc: [0; N_CHANNELS],
+            pwm: [0; N_CHANNELS],
+            pwm_freq: [0; N_CHANNELS],
+            position: 0,
+            delta: 0,
+            encoder_interrupt: false,
+            neopixel_pin: 0,
+            neopixel_speed: 1,
+            neopixel_len: 0,
+            neopixel_buf: [0; NEOPIXEL_BUF_LEN],
+            neopixel_shown: [0; NEOPIXEL_BUF_LEN],
+            shows: 0,
+        }
+    }
+
+    /// Report a raw hardware ID, e.g. to emulate an unknown chip
+    pub fn with_raw_hardware_id(mut self, hardware_id: u8) -> Self {
+        self.hardware_id = hardware_id;
+        self
+    }
+
+    /// Set the product ID reported in the `VERSION` register
+    pub fn with_product_id(mut self, product_id: u16) -> Self {
+        self.version = (self.version & 0xFFFF) | (product_id as u32) << 16;
+        self
+    }
+
+    /// Set the date code reported in the `VERSION` register
+    pub fn with_date_code(mut self, year: u16, month: u8, day: u8) -> Self {
+        self.version = (self.version & 0xFFFF_0000)
+            | (day as u32 & 0x1F) << 11
+            | (month as u32 & 0xF) << 7
+            | (year.saturating_sub(2000) as u32 & 0x3F);
+        self
+    }
+
+    /// Set the raw value of the `OPTIONS` register, one bit per module
+    pub fn with_options(mut self, options: u32) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set the temperature reported by the status module, in °C
+    pub fn with_temperature(mut self, celsius: f32) -> Self {
+        self.temp = (celsius * (1u32 << 16) as f32) as u32;
+        self
+    }
+
+    /// The address the device answers at
+    pub fn addr(&self) -> u8 {
+        self.addr
+    }
+
+    /// Total time spent in delays, in microseconds
+    pub fn elapsed_us(&self) -> u64 {
+        self.elapsed_us
+    }
+
+    /// Number of software resets received
+    pub fn resets(&self) -> u32 {
+        self.resets
+    }
+
+    /// Bitmask of pins configured as outputs
+    pub fn gpio_direction(&self) -> u32 {
+        self.direction
+    }
+
+    /// Bitmask of the output latch, which also selects pullup (1) or pulldown
+    /// (0) for pins with a pull enabled
+    pub fn gpio_output(&self) -> u32 {
+        self.output
+    }
+
+    /// Bitmask of pins with a pullup or pulldown enabled
+    pub fn gpio_pulls(&self) -> u32 {
+        self.pulls
+    }
+
+    /// Bitmask of pins with their change interrupt enabled
+    pub fn gpio_interrupts(&self) -> u32 {
+        self.interrupts
+    }
+
+    /// The level of every pin, as read through the `GPIO` register
+    pub fn gpio_levels(&self) -> u32 {
+        let inputs = (self.driven & self.driven_levels) | (!self.driven & self.pulls & self.output);
+        (self.direction & self.output) | (!self.direction & inputs)
+    }
+
+    /// Drive an input pin externally, e.g. to press a button wired to ground
+    /// use `drive_pin(pin, false)`
+    ///
+    /// # Panics
+    ///
+    /// If `pin` isn't below 32, the width of the GPIO registers
+    pub fn drive_pin(&mut self, pin: u8, high: bool) {
+        assert!(pin < 32, "there is no GPIO pin {pin}");
+        let before = self.gpio_levels();
+        self.driven |= 1 << pin;
+        if high {
+            self.driven_levels |= 1 << pin;
+        } else {
+            self.driven_levels &= !(1 << pin);
+        }
+        self.flag_changes(before);
+    }
+
+    /// Stop driving an input pin, letting it float or follow its pull
+    ///
+    /// # Panics
+    ///
+    /// If `pin` isn't below 32, the width of the GPIO registers
+    pub fn release_pin(&mut self, pin: u8) {
+        assert!(pin < 32, "there is no GPIO pin {pin}");
+        let before = self.gpio_levels();
+        self.driven &= !(1 << pin);
+        self.flag_changes(before);
+    }
+
+    /// Set the raw value of an ADC channel
+    ///
+    /// # Panics
+    ///
+    /// If `channel` isn't below [`N_CHANNELS`]
+    pub fn set_adc_channel(&mut self, channel: u8, value: u16) {
+        self.adc[channel as usize] = value;
+    }
+
+    /// The last duty cycle written to a PWM channel
+    ///
+    /// # Panics
+    ///
+    /// If `channel` isn't below [`N_CHANNELS`]
+    pub fn pwm_channel(&self, channel: u8) -> u16 {
+        self.pwm[channel as usize]
+    }
+
+    /// The last frequency written to a PWM channel
+    ///
+    /// # Panics
+    ///
+    /// If `channel` isn't below [`N_CHANNELS`]
+    pub fn pwm_frequency(&self, channel: u8) -> u16 {
+        self.pwm_freq[channel as usize]
+    }
+
+    /// Turn the encoder by `steps` detents; negative steps turn it backwards
+    pub fn turn(&mut self, steps: i32) {
+        self.position = self.position.wrapping_add(steps);
+        self.delta = self.delta.wrapping_add(steps);
+    }
+
+    /// Whether the encoder interrupt is enabled
+    pub fn encoder_interrupt(&self) -> bool {
+        self.encoder_interrupt
+    }
+
+    /// The pin configured for NeoPixel output
+    pub fn neopixel_pin(&self) -> u8 {
+        self.neopixel_pin
+    }
+
+    /// The configured NeoPixel speed, 0 for 400 kHz and 1 for 800 kHz
+    pub fn neopixel_speed(&self) -> u8 {
+        self.neopixel_speed
+    }
+
+    /// The configured NeoPixel buffer length, in bytes
+    pub fn neopixel_len(&self) -> u16 {
+        self.neopixel_len
+    }
+
+    /// The NeoPixel buffer as last written, which may not have been shown yet
+    pub fn neopixel_buffer(&self) -> &[u8] {
+        &self.neopixel_buf[..(self.neopixel_len as usize).min(NEOPIXEL_BUF_LEN)]
+    }
+
+    /// The bytes of the `n`th RGB pixel as of the last `SHOW`
+    ///
+    /// # Panics
+    ///
+    /// If the pixel doesn't fit in the [`NEOPIXEL_BUF_LEN`] byte buffer
+    pub fn shown_pixel(&self, n: usize) -> (u8, u8, u8) {
+        let p = &self.neopixel_shown[3 * n..3 * n + 3];
+        (p[0], p[1], p[2])
+    }
+
+    /// Number of `SHOW` commands received
+    pub fn shows(&self) -> u32 {
+        self.shows
+    }
+
+    fn flag_changes(&mut self, before: u32) {
+        self.interrupt_flags |= (before ^ self.gpio_levels()) & self.interrupts;
+    }
+
+    fn reset(&mut self) {
+        *self = Self {
+            addr: self.addr,
+            hardware_id: self.hardware_id,
+            version: self.version,
+            options: self.options,
+            temp: self.temp,
+            elapsed_us: self.elapsed_us,
+            resets: self.resets + 1,
+            driven: self.driven,
+            driven_levels: self.driven_levels,
+            adc: self.adc,
+            ..Self::power_on(self.addr, self.hardware_id)
+        };
+    }
+
+    fn handle_write(&mut self, bytes: &[u8]) -> Result<(), SimError> {
+        let (reg, data) = match bytes {
+            [base, func, data @ ..] => ([*base, *func], data),
+            _ => return Ok(()),
+        };
+        self.selected = Some(reg);
+
+        let u32_data = || {
+            let mut buf = [0u8; 4];
+            let len = data.len().min(4);
+            buf[..len].copy_from_slice(&data[..len]);
+            u32::from_be_bytes(buf)
+        };
+        let [base, func] = reg;
+        let unsupported = Err(SimError::UnsupportedRegister(reg));
+
+        match base {
+            b if b == Modules::Status.into_u8() => match func {
+                0x7F if !data.is_empty() => self.reset(),
+                0x01..=0x04 | 0x7F => {}
+                _ => return unsupported,
+            },
+            b if b == Modules::Gpio.into_u8() && data.is_empty() => {}
+            b if b == Modules::Gpio.into_u8() => {
+                let before = self.gpio_levels();
+                let pins = u32_data();
+                match func {
+                    0x02 => self.direction |= pins,
+                    0x03 => self.direction &= !pins,
+                    0x04 => self.output = pins,
+                    0x05 => self.output |= pins,
+                    0x06 => self.output &= !pins,
+                    0x07 => self.output ^= pins,
+                    0x08 => self.interrupts |= pins,
+                    0x09 => self.interrupts &= !pins,
+                    0x0B => self.pulls |= pins,
+                    0x0C => self.pulls &= !pins,
+                    _ => return unsupported,
+                }
+                self.flag_changes(before);
+            }
+            b if b == Modules::Timer.into_u8() => match (func, data) {
+                (0x01 | 0x02, []) => {}
+                (0x01 | 0x02, &[channel, ..]) if channel as usize >= N_CHANNELS => {
+                    return unsupported
+                }
+                (0x01, &[channel, value]) => self.pwm[channel as usize] = value as u16,
+                (0x01, &[channel, hi, lo]) => {
+                    self.pwm[channel as usize] = u16::from_be_bytes([hi, lo])
+                }
+                (0x02, &[channel, hi, lo]) => {
+                    self.pwm_freq[channel as usize] = u16::from_be_bytes([hi, lo])
+                }
+                _ => return unsupported,
+            },
+            b if b == Modules::Adc.into_u8() => match func {
+                0x00 | 0x02 | 0x03 => {}
+                f if f >= 0x07 && ((f - 0x07) as usize) < N_CHANNELS && data.is_empty() => {}
+                _ => return unsupported,
+            },
+            b if b == Modules::Encoder.into_u8() => match (func, data) {
+                (0x00 | 0x30 | 0x40, []) => {}
+                (0x10, [_]) => self.encoder_interrupt = true,
+                (0x20, [_]) => self.encoder_interrupt = false,
+                (0x30, &[a, b, c, d]) => self.position = i32::from_be_bytes([a, b, c, d]),
+                _ => return unsupported,
+            },
+            b if b == Modules::Neopixel.into_u8() => match (func, data) {
+                (0x01, &[pin]) => self.neopixel_pin = pin,
+                (0x02, &[speed]) => self.neopixel_speed = speed,
+                (0x03, &[hi, lo]) => self.neopixel_len = u16::from_be_bytes([hi, lo]),
+                (0x04, &[hi, lo, ref colors @ ..]) => {
+                    let start = u16::from_be_bytes([hi, lo]) as usize;
+                    let end = (start + colors.len()).min(NEOPIXEL_BUF_LEN);
+                    if start < end {
+                        self.neopixel_buf[start..end].copy_from_slice(&colors[..end - start]);
+                    }
+                }
+                (0x05, []) => {
+                    self.neopixel_shown = self.neopixel_buf;
+                    self.shows += 1;
+                }
+                _ => return unsupported,
+            },
+            _ => return unsupported,
+        }
+        Ok(())
+    }
+
+    fn handle_read(&mut self, buffer: &mut [u8]) -> Result<(), SimError> {
+        let reg = self.selected.ok_or(SimError::NoRegisterSelected)?;
+        let [base, func] = reg;
+        let unsupported = Err(SimError::UnsupportedRegister(reg));
+
+        let mut value = [0u8; 4];
+        match base {
+            b if b == Modules::Status.into_u8() => match func {
+                0x01 => value[0] = self.hardware_id,
+                0x02 => value = self.version.to_be_bytes(),
+                0x03 => value = self.options.to_be_bytes(),
+                0x04 => value = self.temp.to_be_bytes(),
+                _ => return unsupported,
+            },
+            b if b == Modules::Gpio.into_u8() => match func {
+                0x04 => value = self.gpio_levels().to_be_bytes(),
+                0x0A => {
+                    value = self.interrupt_flags.to_be_bytes();
+                    self.interrupt_flags = 0;
+                }
+                _ => return unsupported,
+            },
+            b if b == Modules::Adc.into_u8() => match func {
+                f if f >= 0x07 && ((f - 0x07) as usize) < N_CHANNELS => {
+                    value[..2].copy_from_slice(&self.adc[(f - 0x07) as usize].to_be_bytes())
+                }
+                _ => return unsupported,
+            },
+            b if b == Modules::Encoder.into_u8() => match func {
+                0x30 => value = self.position.to_be_bytes(),
+                0x40 => {
+                    value = self.delta.to_be_bytes();
+                    self.delta = 0;
+                }
+                _ => return unsupported,
+            },
+            _ => return unsupported,
+        }
+
+        let len = buffer.len().min(value.len());
+        buffer[..len].copy_from_slice(&value[..len]);
+        Ok(())
+    }
+
+    fn check_addr(&self, addr: u8) -> Result<(), SimError> {
+        match addr == self.addr {
+            true => Ok(()),
+            false => Err(SimError::Nack(addr)),
+        }
+    }
+}
+
+impl i2c::Write for SimDevice {
+    type Error = SimError;
+
+    fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.check_addr(addr)?;
+        self.handle_write(bytes)
+    }
+}
+
+impl i2c::Read for SimDevice {
+    type Error = SimError;
+
+    fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.check_addr(addr)?;
+        self.handle_read(buffer)
+    }
+}
+
+impl i2c::WriteRead for SimDevice {
+    type Error = SimError;
+
+    fn write_read(&mut self, addr: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.check_addr(addr)?;
+        self.handle_write(bytes)?;
+        self.handle_read(buffer)
+    }
+}
+
+impl delay::DelayUs<u32> for SimDevice {
+    fn delay_us(&mut self, us: u32) {
+        self.elapsed_us += us as u64;
+    }
+}
+
+/// Several emulated devices sharing one bus. Transactions are routed by
+/// address, and addresses without a device are NACKed.
+#[derive(Clone, Debug)]
+pub struct SimBus<const N: usize> {
+    devices: [SimDevice; N],
+    elapsed_us: u64,
+}
+
+impl<const N: usize> SimBus<N> {
+    pub fn new(devices: [SimDevice; N]) -> Self {
+        Self {
+            devices,
+            elapsed_us: 0,
+        }
+    }
+
+    /// The device at `addr`, if any
+    pub fn device(&mut self, addr: u8) -> Option<&mut SimDevice> {
+        self.devices.iter_mut().find(|device| device.addr == addr)
+    }
+
+    /// Total time spent in delays, in microseconds
+    pub fn elapsed_us(&self) -> u64 {
+        self.elapsed_us
+    }
+
+    fn route(&mut self, addr: u8) -> Result<&mut SimDevice, SimError> {
+        self.device(addr).ok_or(SimError::Nack(addr))
+    }
+}
+
+impl<const N: usize> i2c::Write for SimBus<N> {
+    type Error = SimError;
+
+    fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.route(addr)?.write(addr, bytes)
+    }
+}
+
+impl<const N: usize> i2c::Read for SimBus<N> {
+    type Error = SimError;
+
+    fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.route(addr)?.read(addr, buffer)
+    }
+}
+
+impl<const N: usize> i2c::WriteRead for SimBus<N> {
+    type Error = SimError;
+
+    fn write_read(&mut self, addr: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.route(addr)?.write_read(addr, bytes, buffer)
+    }
+}
+
+impl<const N: usize> delay::DelayUs<u32> for SimBus<N> {
+    fn delay_us(&mut self, us: u32) {
+        self.elapsed_us += us as u64;
+    }
+}
+
+/// A delay that returns immediately, for sharing a [`SimDevice`] through a
+/// [`Seesaw`](crate::Seesaw)
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SimDelay {
+    elapsed_us: u64,
+}
+
+impl SimDelay {
+    /// Total time spent in delays, in microseconds
+    pub fn elapsed_us(&self) -> u64 {
+        self.elapsed_us
+    }
+}
+
+impl delay::DelayUs<u32> for SimDelay {
+    fn delay_us(&mut self, us: u32) {
+        self.elapsed_us += us as u64;
+    }
+}
+
+#[cfg(feature = "embedded-hal-1")]
+mod eh1_impls {
+    use super::{SimDevice, SimError};
+    use embedded_hal_1::i2c::{Error, ErrorKind, ErrorType, NoAcknowledgeSource, Operation};
+
+    impl Error for SimError {
+        fn kind(&self) -> ErrorKind {
+            match self {
+                SimError::Nack(_) => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address),
+                _ => ErrorKind::Other,
+            }
+        }
+    }
+
+    impl ErrorType for SimDevice {
+        type Error = SimError;
+    }
+
+    impl SimDevice {
+        fn run(&mut self, addr: u8, operations: &mut [Operation<'_>]) -> Result<(), SimError> {
+            self.check_addr(addr)?;
+            operations.iter_mut().try_for_each(|op| match op {
+                Operation::Write(bytes) => self.handle_write(bytes),
+                Operation::Read(buffer) => self.handle_read(buffer),
+            })
+        }
+    }
+
+    impl embedded_hal_1::i2c::I2c for SimDevice {
+        fn transaction(
+            &mut self,
+            addr: u8,
+            operations: &mut [Operation<'_>],
+        ) -> Result<(), Self::Error> {
+            self.run(addr, operations)
+        }
+    }
+
+    impl embedded_hal_1::delay::DelayNs for SimDevice {
+        fn delay_ns(&mut self, ns: u32) {
+            self.elapsed_us += ns.div_ceil(1_000) as u64;
+        }
+    }
+
+    #[cfg(feature = "async")]
+    impl embedded_hal_async::i2c::I2c for SimDevice {
+        async fn transaction(
+            &mut self,
+            addr: u8,
+            operations: &mut [Operation<'_>],
+        ) -> Result<(), Self::Error> {
+            self.run(addr, operations)
+        }
+    }
+
+    #[cfg(feature = "async")]
+    impl embedded_hal_async::delay::DelayNs for SimDevice {
+        async fn delay_ns(&mut self, ns: u32) {
+            self.elapsed_us += ns.div_ceil(1_000) as u64;
+        }
+    }
+}
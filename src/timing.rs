@@ -0,0 +1,86 @@
+use crate::common::HardwareId;
+
+/// How long to wait around register accesses and slow operations, in
+/// microseconds.
+///
+/// Each device starts with the profile for its [`HardwareId`], which can be
+/// replaced with
+/// [`SeesawDevice::new_with_timing`](crate::SeesawDevice::new_with_timing):
+///
+/// ```
+/// # use adafruit_seesaw::{HardwareId, Timing};
+/// let timing = Timing {
+///     reset_delay_us: 250_000,
+///     ..Timing::for_hardware(HardwareId::SAMD09)
+/// };
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Timing {
+    /// Between writing a register's address and reading its value
+    pub read_delay_us: u32,
+    /// After every register write
+    pub write_delay_us: u32,
+    /// After a software reset, while the device restarts
+    pub reset_delay_us: u32,
+    /// Between selecting an ADC channel and reading its conversion. The
+    /// seesaw needs at least 500us here, and at least 1ms between reads of
+    /// different channels, so the default covers both.
+    pub adc_delay_us: u32,
+    /// After setting the NeoPixel pin, length or speed
+    pub neopixel_delay_us: u32,
+    /// Between selecting a touch channel and reading its measurement
+    pub touch_delay_us: u32,
+}
+
+impl Timing {
+    /// The ATtiny8x7 has no bootloader to wait for after a reset, and its ADC
+    /// converts a channel in well under the SAMD09's 500us.
+    pub const ATTINY817: Self = Self {
+        reset_delay_us: 50_000,
+        adc_delay_us: 500,
+        ..Self::SAMD09
+    };
+    /// The delays used by a bare driver, and while a device's chip isn't
+    /// known yet. These are the SAMD09's, which are the slower of the two.
+    pub const DEFAULT: Self = Self::SAMD09;
+    /// The SAMD09 waits in its bootloader for a while after a reset, and its
+    /// ADC needs 500us after a channel is selected and 1ms between channels.
+    pub const SAMD09: Self = Self {
+        read_delay_us: 125,
+        write_delay_us: 125,
+        reset_delay_us: 125_000,
+        adc_delay_us: 1_000,
+        neopixel_delay_us: 10_000,
+        touch_delay_us: 3_000,
+    };
+
+    /// The default profile for a chip
+    pub const fn for_hardware(hardware_id: HardwareId) -> Self {
+        match hardware_id {
+            HardwareId::ATTINY817 => Self::ATTINY817,
+            HardwareId::SAMD09 => Self::SAMD09,
+        }
+    }
+}
+
+impl Default for Timing {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chips_have_their_own_profiles() {
+        let attiny = Timing::for_hardware(HardwareId::ATTINY817);
+        let samd09 = Timing::for_hardware(HardwareId::SAMD09);
+        assert!(attiny.reset_delay_us < samd09.reset_delay_us);
+        assert!(attiny.adc_delay_us < samd09.adc_delay_us);
+        assert_eq!(attiny.read_delay_us, samd09.read_delay_us);
+        assert_eq!(Timing::default(), samd09);
+    }
+}
@@ -0,0 +1,485 @@
+//! Recording and replaying the I2C transactions of a driver.
+//!
+//! [`TracingDriver`] wraps any [`Driver`] and hands every I2C transaction it
+//! makes to a [`Sink`], such as a [`RingBuffer`] or a closure, stamped with
+//! the time from a user-supplied [`Clock`]. A recorded trace can be played
+//! back with [`Replay`], which acts as a driver that expects exactly the same
+//! transactions, for regression tests.
+//!
+//! ```ignore
+//! let mut ticks = 0;
+//! let clock = move || {
+//!     ticks += 1;
+//!     ticks
+//! };
+//! let driver = TracingDriver::new(seesaw.acquire_driver(), clock, RingBuffer::<_, 64>::default());
+//! let mut neokeys = NeoKey1x4::new_with_default_addr(driver).init()?;
+//!
+//! for transaction in neokeys.driver().sink().iter() {
+//!     rprintln!("{:?}", transaction);
+//! }
+//!
+//! // Later, in a test
+//! let mut replay = Replay::new(recorded);
+//! let neokeys = NeoKey1x4::new_with_default_addr(replay).init()?;
+//! neokeys.driver().done();
+//! ```
+use crate::{
+    common::Reg,
+    driver::{Driver, MAX_WRITE_LEN},
+};
+use embedded_hal::blocking::{delay, i2c};
+
+/// The most bytes a [`Transaction`] keeps: a register address and a full
+/// register write
+pub const MAX_DATA_LEN: usize = MAX_WRITE_LEN + 2;
+
+/// The direction of an I2C transaction
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Direction {
+    Write,
+    Read,
+}
+
+/// A single I2C transaction.
+///
+/// A register read is recorded as a write of the register address followed by
+/// a read of its value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Transaction<E> {
+    /// When the transaction started, from the tracing driver's [`Clock`]
+    pub timestamp: u64,
+    pub addr: u8,
+    pub direction: Direction,
+    /// The bus's result
+    pub result: Result<(), E>,
+    data: [u8; MAX_DATA_LEN],
+    len: usize,
+}
+
+impl<E> Transaction<E> {
+    /// Start a transaction of `data`; `data` is cut off after
+    /// [`MAX_DATA_LEN`] bytes
+    pub fn new(timestamp: u64, addr: u8, direction: Direction, data: &[u8]) -> Self {
+        let len = data.len().min(MAX_DATA_LEN);
+        let mut buffer = [0; MAX_DATA_LEN];
+        buffer[..len].copy_from_slice(&data[..len]);
+        Self {
+            timestamp,
+            addr,
+            direction,
+            result: Ok(()),
+            data: buffer,
+            len,
+        }
+    }
+
+    pub fn with_result(mut self, result: Result<(), E>) -> Self {
+        self.result = result;
+        self
+    }
+
+    /// The bytes written or read
+    pub fn data(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+
+    /// The register addressed by a write
+    pub fn reg(&self) -> Option<Reg> {
+        match (self.direction, self.data()) {
+            (Direction::Write, [base, function, ..]) => Some([*base, *function]),
+            _ => None,
+        }
+    }
+
+    /// The bytes after the register address of a write, or all of the bytes
+    /// of a read
+    pub fn payload(&self) -> &[u8] {
+        match self.direction {
+            Direction::Write => self.data().get(2..).unwrap_or_default(),
+            Direction::Read => self.data(),
+        }
+    }
+}
+
+/// A source of timestamps, in whatever unit suits the application
+pub trait Clock {
+    fn now(&mut self) -> u64;
+}
+
+impl<F: FnMut() -> u64> Clock for F {
+    fn now(&mut self) -> u64 {
+        self()
+    }
+}
+
+/// Somewhere to put recorded transactions
+pub trait Sink<E> {
+    fn record(&mut self, transaction: Transaction<E>);
+}
+
+impl<E, F: FnMut(Transaction<E>)> Sink<E> for F {
+    fn record(&mut self, transaction: Transaction<E>) {
+        self(transaction)
+    }
+}
+
+/// Keeps the last `N` transactions, dropping the oldest
+#[derive(Clone, Debug)]
+pub struct RingBuffer<E, const N: usize> {
+    entries: [Option<Transaction<E>>; N],
+    next: usize,
+    len: usize,
+    dropped: usize,
+}
+
+impl<E, const N: usize> RingBuffer<E, N> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of transactions that were overwritten by newer ones
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// The transactions, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = &Transaction<E>> {
+        let (newer, older) = self.entries.split_at(self.next);
+        older.iter().chain(newer).flatten()
+    }
+}
+
+impl<E, const N: usize> Default for RingBuffer<E, N> {
+    fn default() -> Self {
+        Self {
+            entries: core::array::from_fn(|_| None),
+            next: 0,
+            len: 0,
+            dropped: 0,
+        }
+    }
+}
+
+impl<E, const N: usize> Sink<E> for RingBuffer<E, N> {
+    fn record(&mut self, transaction: Transaction<E>) {
+        if N == 0 {
+            self.dropped += 1;
+            return;
+        }
+        match self.entries[self.next].replace(transaction) {
+            Some(_) => self.dropped += 1,
+            None => self.len += 1,
+        }
+        self.next = (self.next + 1) % N;
+    }
+}
+
+/// A driver that records every I2C transaction of the driver it wraps
+#[derive(Debug)]
+pub struct TracingDriver<D, C, S> {
+    driver: D,
+    clock: C,
+    sink: S,
+}
+
+impl<D: Driver, C: Clock, S: Sink<D::I2cError>> TracingDriver<D, C, S>
+where
+    D::I2cError: Clone,
+{
+    pub fn new(driver: D, clock: C, sink: S) -> Self {
+        Self {
+            driver,
+            clock,
+            sink,
+        }
+    }
+
+    pub fn driver(&mut self) -> &mut D {
+        &mut self.driver
+    }
+
+    pub fn sink(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    pub fn release(self) -> (D, C, S) {
+        (self.driver, self.clock, self.sink)
+    }
+}
+
+impl<D, C, S> i2c::Write for TracingDriver<D, C, S>
+where
+    D: Driver,
+    D::I2cError: Clone,
+    C: Clock,
+    S: Sink<D::I2cError>,
+{
+    type Error = D::I2cError;
+
+    fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        let transaction = Transaction::new(self.clock.now(), addr, Direction::Write, bytes);
+        let result = self.driver.write(addr, bytes).map_err(Self::Error::from);
+        self.sink.record(transaction.with_result(result.clone()));
+        result
+    }
+}
+
+impl<D, C, S> i2c::Read for TracingDriver<D, C, S>
+where
+    D: Driver,
+    D::I2cError: Clone,
+    C: Clock,
+    S: Sink<D::I2cError>,
+{
+    type Error = D::I2cError;
+
+    fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        let timestamp = self.clock.now();
+        let result = self.driver.read(addr, buffer).map_err(Self::Error::from);
+        self.sink.record(
+            Transaction::new(timestamp, addr, Direction::Read, buffer).with_result(result.clone()),
+        );
+        result
+    }
+}
+
+impl<D, C, S> i2c::WriteRead for TracingDriver<D, C, S>
+where
+    D: Driver,
+    D::I2cError: Clone,
+    C: Clock,
+    S: Sink<D::I2cError>,
+{
+    type Error = D::I2cError;
+
+    fn write_read(&mut self, addr: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), Self::Error> {
+        let timestamp = self.clock.now();
+        let result = self
+            .driver
+            .write_read(addr, bytes, buffer)
+            .map_err(Self::Error::from);
+        self.sink.record(
+            Transaction::new(timestamp, addr, Direction::Write, bytes).with_result(result.clone()),
+        );
+        self.sink.record(
+            Transaction::new(timestamp, addr, Direction::Read, buffer).with_result(result.clone()),
+        );
+        result
+    }
+}
+
+impl<D: Driver, C, S> delay::DelayUs<u32> for TracingDriver<D, C, S> {
+    fn delay_us(&mut self, us: u32) {
+        self.driver.delay_us(us)
+    }
+}
+
+/// A driver that plays back a recorded trace: it expects the same
+/// transactions, in the same order, and answers reads with the recorded
+/// bytes and results.
+///
+/// # Panics
+///
+/// On a transaction that doesn't match the next one in the trace, or that
+/// comes after the end of the trace.
+#[derive(Debug)]
+pub struct Replay<I> {
+    trace: I,
+    position: usize,
+}
+
+impl<I, E> Replay<I>
+where
+    I: Iterator<Item = Transaction<E>>,
+    E: core::fmt::Debug,
+{
+    pub fn new(trace: impl IntoIterator<IntoIter = I>) -> Self {
+        Self {
+            trace: trace.into_iter(),
+            position: 0,
+        }
+    }
+
+    /// Assert that the whole trace was played back
+    pub fn done(&mut self) {
+        if let Some(transaction) = self.trace.next() {
+            panic!(
+                "transaction {} was never played back: {transaction:?}",
+                self.position
+            );
+        }
+    }
+
+    fn expect(&mut self, addr: u8, direction: Direction) -> Transaction<E> {
+        let position = self.position;
+        let transaction = self.trace.next().unwrap_or_else(|| {
+            panic!("unexpected {direction:?} at {addr:#04x} after the end of the trace")
+        });
+        assert_eq!(
+            (transaction.addr, transaction.direction),
+            (addr, direction),
+            "transaction {position} doesn't match the trace"
+        );
+        self.position += 1;
+        transaction
+    }
+}
+
+impl<I, E> i2c::Write for Replay<I>
+where
+    I: Iterator<Item = Transaction<E>>,
+    E: core::fmt::Debug,
+{
+    type Error = E;
+
+    fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        let position = self.position;
+        let expected = self.expect(addr, Direction::Write);
+        assert_eq!(
+            &bytes[..bytes.len().min(MAX_DATA_LEN)],
+            expected.data(),
+            "transaction {position} wrote different bytes than the trace"
+        );
+        expected.result
+    }
+}
+
+impl<I, E> i2c::Read for Replay<I>
+where
+    I: Iterator<Item = Transaction<E>>,
+    E: core::fmt::Debug,
+{
+    type Error = E;
+
+    fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        let position = self.position;
+        let expected = self.expect(addr, Direction::Read);
+        assert_eq!(
+            buffer.len(),
+            expected.data().len(),
+            "transaction {position} read a different number of bytes than the trace"
+        );
+        buffer.copy_from_slice(expected.data());
+        expected.result
+    }
+}
+
+impl<I, E> i2c::WriteRead for Replay<I>
+where
+    I: Iterator<Item = Transaction<E>>,
+    E: core::fmt::Debug,
+{
+    type Error = E;
+
+    fn write_read(&mut self, addr: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), Self::Error> {
+        i2c::Write::write(self, addr, bytes).and_then(|_| i2c::Read::read(self, addr, buffer))
+    }
+}
+
+impl<I> delay::DelayUs<u32> for Replay<I> {
+    fn delay_us(&mut self, _us: u32) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, Replay, RingBuffer, Sink, TracingDriver, Transaction};
+    use crate::{
+        devices::NeoKey1x4,
+        mock::{read, write, MockDriver},
+        modules::gpio::GpioModule,
+        SeesawDevice,
+    };
+    use embedded_hal_mock::eh0::MockError;
+
+    const ADDR: u8 = 0x30;
+
+    fn counter() -> impl FnMut() -> u64 {
+        let mut ticks = 0;
+        move || {
+            ticks += 10;
+            ticks
+        }
+    }
+
+    #[test]
+    fn records_register_accesses() {
+        let bus = MockDriver::new(
+            &[
+                vec![write(ADDR, [0x01, 0x05], &[0, 0, 0, 0x10])],
+                read(ADDR, [0x01, 0x04], &[0, 0, 0, 0xF0]).to_vec(),
+            ]
+            .concat(),
+        );
+        let driver = TracingDriver::new(bus, counter(), RingBuffer::<_, 4>::default());
+        let mut dev = NeoKey1x4::new(ADDR, driver);
+        dev.digital_write(4, true).unwrap();
+        assert_eq!(dev.digital_read_bulk().unwrap(), 0xF0);
+
+        let trace: Vec<_> = dev.driver().sink().iter().cloned().collect();
+        assert_eq!(
+            trace,
+            [
+                Transaction::new(10, ADDR, Direction::Write, &[0x01, 0x05, 0, 0, 0, 0x10]),
+                Transaction::new(20, ADDR, Direction::Write, &[0x01, 0x04]),
+                Transaction::new(30, ADDR, Direction::Read, &[0, 0, 0, 0xF0]),
+            ]
+        );
+        assert_eq!(trace[0].reg(), Some([0x01, 0x05]));
+        assert_eq!(trace[0].payload(), [0, 0, 0, 0x10]);
+        assert_eq!(trace[2].reg(), None);
+        dev.driver().driver().done();
+    }
+
+    #[test]
+    fn ring_buffer_keeps_newest() {
+        let mut buffer = RingBuffer::<(), 2>::default();
+        (0..5).for_each(|i| buffer.record(Transaction::new(i, ADDR, Direction::Read, &[])));
+        assert_eq!(buffer.len(), 2);
+        assert_eq!(buffer.dropped(), 3);
+        assert_eq!(
+            buffer.iter().map(|t| t.timestamp).collect::<Vec<_>>(),
+            [3, 4]
+        );
+        buffer.clear();
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn replays_trace() {
+        let trace = [
+            Transaction::new(0, ADDR, Direction::Write, &[0x01, 0x04]),
+            Transaction::new(0, ADDR, Direction::Read, &[0, 0, 0, 0x0F]),
+            Transaction::new(0, ADDR, Direction::Write, &[0x01, 0x04]),
+            Transaction::new(0, ADDR, Direction::Read, &[0, 0, 0, 0])
+                .with_result(Err(MockError::Io(std::io::ErrorKind::Other))),
+        ];
+        let mut dev = NeoKey1x4::new(ADDR, Replay::new(trace));
+        assert_eq!(dev.digital_read_bulk().unwrap(), 0x0F);
+        assert!(dev.digital_read_bulk().is_err());
+        dev.driver().done();
+    }
+
+    #[test]
+    #[should_panic(expected = "transaction 0 wrote different bytes than the trace")]
+    fn replay_rejects_different_write() {
+        let trace = [Transaction::<MockError>::new(
+            0,
+            ADDR,
+            Direction::Write,
+            &[0x01, 0x05, 0, 0, 0, 0x10],
+        )];
+        let mut dev = NeoKey1x4::new(ADDR, Replay::new(trace));
+        let _ = dev.digital_write(5, true);
+    }
+}
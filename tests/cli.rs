@@ -0,0 +1,144 @@
+use adafruit_seesaw::{
+    cli::{self, CliError},
+    prelude::*,
+    sim::{SimBus, SimDelay, SimDevice, SimError},
+    Modules, SeesawError, SeesawSingleThread,
+};
+
+type SimSeesaw = SeesawSingleThread<adafruit_seesaw::bus::Bus<SimDelay, SimBus<2>>>;
+
+fn seesaw() -> SimSeesaw {
+    let mut encoder = SimDevice::new(0x36, RotaryEncoder::hardware_id())
+        .with_product_id(RotaryEncoder::product_id())
+        .with_date_code(2021, 7, 4)
+        .with_temperature(25.5);
+    encoder.turn(3);
+    let options = [
+        Modules::Status,
+        Modules::Gpio,
+        Modules::Adc,
+        Modules::Neopixel,
+    ]
+    .iter()
+    .fold(0, |opts, &module| opts | 1 << module.into_u8());
+    let mut slider = SimDevice::new(0x30, NeoSlider::hardware_id())
+        .with_product_id(NeoSlider::product_id())
+        .with_options(options);
+    slider.set_adc_channel(18, 700);
+    SeesawSingleThread::new(SimDelay::default(), SimBus::new([encoder, slider]))
+}
+
+fn cli(seesaw: &SimSeesaw, args: &str) -> Result<String, CliError<SimError>> {
+    let args: Vec<&str> = args.split_whitespace().collect();
+    let mut out = Vec::new();
+    cli::run(seesaw.acquire_driver(), &mut out, &args)?;
+    Ok(String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn scan() {
+    assert_eq!(
+        cli(&seesaw(), "scan").unwrap(),
+        concat!(
+            "0x30  ATTINY817  product 5295 (NeoSlider)\n",
+            "0x36  SAMD09  product 4991 (RotaryEncoder)\n",
+        )
+    );
+}
+
+#[test]
+fn info() {
+    assert_eq!(
+        cli(&seesaw(), "info 0x36").unwrap(),
+        concat!(
+            "address:      0x36\n",
+            "hardware id:  SAMD09 (0x55)\n",
+            "product:      4991 (RotaryEncoder)\n",
+            "date code:    2021-07-04\n",
+            "capabilities: status gpio timer adc neopixel encoder\n",
+            "temperature:  25.50 C\n",
+        )
+    );
+}
+
+#[test]
+fn gpio() {
+    let seesaw = seesaw();
+    cli(&seesaw, "gpio mode 0x30 4 output").unwrap();
+    cli(&seesaw, "gpio write 0x30 4 high").unwrap();
+    assert_eq!(cli(&seesaw, "gpio read 0x30 4").unwrap(), "high\n");
+    cli(&seesaw, "gpio write 0x30 4 low").unwrap();
+    assert_eq!(cli(&seesaw, "gpio read 0x30 4").unwrap(), "low\n");
+}
+
+#[test]
+fn adc() {
+    assert_eq!(cli(&seesaw(), "adc read 0x30 18").unwrap(), "700\n");
+}
+
+#[test]
+fn pixel() {
+    let seesaw = seesaw();
+    cli(&seesaw, "pixel set 0x30 14 4 1 #102030").unwrap();
+    cli(&seesaw, "pixel set 0x30 14 4 3 405060").unwrap();
+}
+
+#[test]
+fn reg() {
+    let seesaw = seesaw();
+    // STATUS_HW_ID
+    assert_eq!(cli(&seesaw, "reg read 0x30 0x00 0x01 1").unwrap(), "0x87\n");
+    // ENCODER_POSITION
+    cli(&seesaw, "reg write 0x36 0x11 0x30 0 0 1 2").unwrap();
+    assert_eq!(
+        cli(&seesaw, "reg read 0x36 0x11 0x30 4").unwrap(),
+        "0x00000102\n"
+    );
+    assert_eq!(
+        cli(&seesaw, "reg read 0x36 0x11 0x30 2").unwrap(),
+        "0x0000\n"
+    );
+}
+
+#[test]
+fn encoder_watch() {
+    assert_eq!(cli(&seesaw(), "encoder watch 0x36 1").unwrap(), "3 (+3)\n");
+}
+
+#[test]
+fn errors() {
+    let seesaw = seesaw();
+    assert!(matches!(cli(&seesaw, "frobnicate"), Err(CliError::Usage)));
+    assert!(matches!(
+        cli(&seesaw, "info 0x50"),
+        Err(CliError::NoDevice(0x50))
+    ));
+    assert!(matches!(
+        cli(&seesaw, "gpio write 0x30 4 sideways"),
+        Err(CliError::InvalidArgument(arg)) if arg == "sideways"
+    ));
+    assert!(matches!(
+        cli(&seesaw, "pixel set 0x30 14 4 4 ffffff"),
+        Err(CliError::InvalidArgument(arg)) if arg == "4"
+    ));
+    assert!(matches!(
+        cli(&seesaw, "gpio read 0x30 40"),
+        Err(CliError::Seesaw(SeesawError::InvalidPin {
+            module: Modules::Gpio,
+            pin: 40
+        }))
+    ));
+    assert!(matches!(
+        cli(&seesaw, "pixel set 0x30 14 30000 0 ff0000"),
+        Err(CliError::Seesaw(SeesawError::PixelOutOfRange {
+            index: 60,
+            len: 60
+        }))
+    ));
+    assert!(matches!(
+        cli(&seesaw, "encoder watch 0x30 1"),
+        Err(CliError::Seesaw(SeesawError::ModuleNotSupported(
+            Modules::Encoder
+        )))
+    ));
+}
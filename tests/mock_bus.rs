@@ -0,0 +1,129 @@
+use adafruit_seesaw::{prelude::*, SeesawError, SeesawSingleThread};
+use embedded_hal_mock::eh0::{
+    delay::NoopDelay,
+    i2c::{Mock as I2cMock, Transaction},
+};
+
+fn write(addr: u8, bytes: &[u8]) -> Transaction {
+    Transaction::write(addr, bytes.to_vec())
+}
+
+fn read(addr: u8, reg: &[u8], response: &[u8]) -> [Transaction; 2] {
+    [
+        Transaction::write(addr, reg.to_vec()),
+        Transaction::read(addr, response.to_vec()),
+    ]
+}
+
+#[test]
+fn neokey_init_through_shared_bus() {
+    let addr = NeoKey1x4::default_addr();
+    let expectations = [
+        vec![write(addr, &[0x00, 0x7F, 0xFF])],
+        read(addr, &[0x00, 0x01], &[0x55]).to_vec(),
+        vec![
+            write(addr, &[0x0E, 0x01, 0x03]),
+            write(addr, &[0x0E, 0x03, 0x00, 0x0C]),
+            write(addr, &[0x01, 0x03, 0x00, 0x00, 0x00, 0xF0]),
+            write(addr, &[0x01, 0x0B, 0x00, 0x00, 0x00, 0xF0]),
+            write(addr, &[0x01, 0x05, 0x00, 0x00, 0x00, 0xF0]),
+        ],
+    ]
+    .concat();
+    let mut i2c = I2cMock::new(&expectations);
+    let seesaw = SeesawSingleThread::new(NoopDelay::new(), i2c.clone());
+
+    NeoKey1x4::new_with_default_addr(seesaw.acquire_driver())
+        .init()
+        .expect("Failed to start NeoKey1x4");
+    i2c.done();
+}
+
+#[test]
+fn init_rejects_wrong_hardware_id() {
+    let addr = RotaryEncoder::default_addr();
+    let expectations = [
+        vec![write(addr, &[0x00, 0x7F, 0xFF])],
+        read(addr, &[0x00, 0x01], &[0x87]).to_vec(),
+    ]
+    .concat();
+    let mut i2c = I2cMock::new(&expectations);
+    let seesaw = SeesawSingleThread::new(NoopDelay::new(), i2c.clone());
+
+    let result = RotaryEncoder::new_with_default_addr(seesaw.acquire_driver()).init();
+    assert!(matches!(result, Err(SeesawError::InvalidHardwareId(0x87))));
+    i2c.done();
+}
+
+#[test]
+fn product_info_and_capabilities() {
+    let addr = NeoSlider::default_addr();
+    // Product 5295, made 2022-03-14; GPIO, ADC and NeoPixel modules
+    let version = (5295u32 << 16) | (14 << 11) | (3 << 7) | 22;
+    let options = (1u32 << 0x01) | (1 << 0x09) | (1 << 0x0E);
+    let expectations = [
+        read(addr, &[0x00, 0x02], &version.to_be_bytes()),
+        read(addr, &[0x00, 0x03], &options.to_be_bytes()),
+    ]
+    .concat();
+    let mut i2c = I2cMock::new(&expectations);
+    let seesaw = SeesawSingleThread::new(NoopDelay::new(), i2c.clone());
+    let mut slider = NeoSlider::new_with_default_addr(seesaw.acquire_driver());
+
+    let info = slider.product_info().unwrap();
+    assert_eq!(
+        (info.id, info.year, info.month, info.day),
+        (5295, 2022, 3, 14)
+    );
+    let caps = slider.capabilities().unwrap();
+    assert!(caps.gpio && caps.adc && caps.neopixel);
+    assert!(!caps.encoder && !caps.timer);
+    i2c.done();
+}
+
+#[test]
+fn long_register_writes_are_rejected() {
+    let mut i2c = I2cMock::new(&[write(0x30, &[[0x0E, 0x04].as_slice(), &[7; 32]].concat())]);
+    let seesaw = SeesawSingleThread::new(NoopDelay::new(), i2c.clone());
+    let mut driver = seesaw.acquire_driver();
+
+    driver
+        .register_write(0x30, &[0x0E, 0x04], &[7; 32])
+        .unwrap();
+    assert!(matches!(
+        driver.register_write(0x30, &[0x0E, 0x04], &[7; 33]),
+        Err(SeesawError::WriteTooLong {
+            reg: [0x0E, 0x04],
+            len: 33
+        })
+    ));
+    i2c.done();
+}
+
+#[test]
+fn neopixel_brightness_scales_every_write() {
+    let addr = NeoKey1x4::default_addr();
+    let expectations = [
+        write(addr, &[0x0E, 0x04, 0x00, 0x03, 0x7F, 0x40, 0x00]),
+        write(addr, &[0x0E, 0x04, 0x00, 0x00, 0x40, 0x20, 0x10]),
+        write(
+            addr,
+            &[0x0E, 0x04, 0x00, 0x00, 0x00, 0x20, 0x10, 0x7F, 0x7F, 0x7F],
+        ),
+        write(addr, &[0x0E, 0x04, 0x00, 0x06, 0xFF, 0x00, 0x00]),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let seesaw = SeesawSingleThread::new(NoopDelay::new(), i2c.clone());
+    let mut neokeys = NeoKey1x4::new_with_default_addr(seesaw.acquire_driver());
+
+    assert_eq!(neokeys.neopixel_brightness(), 255);
+    neokeys.set_neopixel_brightness(127);
+    neokeys.set_nth_neopixel_color(1, 0xFF, 0x80, 0x00).unwrap();
+    neokeys.set_neopixel_colors(&[(0x80, 0x40, 0x20)]).unwrap();
+    neokeys
+        .set_neopixel_rgbs(&[Rgb::new(0x00, 0x40, 0x20), Rgb::WHITE])
+        .unwrap();
+    neokeys.set_neopixel_brightness(255);
+    neokeys.set_nth_neopixel_rgb(2, Rgb::RED).unwrap();
+    i2c.done();
+}
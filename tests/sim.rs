@@ -0,0 +1,341 @@
+use adafruit_seesaw::{
+    bus,
+    prelude::*,
+    sim::{SimBus, SimDelay, SimDevice, SimError, N_CHANNELS},
+    trace::{Replay, RingBuffer, TracingDriver},
+    AnyDevice, DynamicDevice, HardwareId, KnownDevice, Modules, SeesawError, SeesawSingleThread,
+    Timing,
+};
+use embedded_hal::digital::v2::{InputPin, OutputPin, ToggleableOutputPin};
+
+#[test]
+fn neokey_keys_through_shared_bus() {
+    let sim = SimDevice::new(NeoKey1x4::default_addr(), NeoKey1x4::hardware_id());
+    let seesaw = SeesawSingleThread::new(SimDelay::default(), sim);
+    let mut neokeys = NeoKey1x4::new_with_default_addr(seesaw.acquire_driver())
+        .init()
+        .expect("Failed to start NeoKey1x4");
+
+    assert_eq!(neokeys.keys().unwrap(), 0b1111);
+    neokeys
+        .set_neopixel_colors(&[(1, 2, 3), (4, 5, 6), (7, 8, 9), (10, 11, 12)])
+        .and_then(|_| neokeys.sync_neopixel())
+        .unwrap();
+}
+
+#[test]
+fn neokey_press_and_pixels() {
+    let sim = SimDevice::new(NeoKey1x4::default_addr(), NeoKey1x4::hardware_id());
+    let mut neokeys = NeoKey1x4::new_with_default_addr(sim).init().unwrap();
+
+    neokeys.driver().drive_pin(6, false);
+    assert_eq!(neokeys.keys().unwrap(), 0b1011);
+
+    neokeys.set_nth_neopixel_color(2, 10, 20, 30).unwrap();
+    assert_eq!(neokeys.driver().shows(), 0);
+    neokeys.sync_neopixel().unwrap();
+    let sim = neokeys.driver();
+    assert_eq!(sim.neopixel_pin(), 3);
+    assert_eq!(sim.neopixel_len(), 12);
+    assert_eq!(sim.shown_pixel(1), (0, 0, 0));
+    assert_eq!(sim.shown_pixel(2), (10, 20, 30));
+    assert_eq!(sim.shown_pixel(3), (0, 0, 0));
+}
+
+#[test]
+fn rotary_encoder_position_and_delta() {
+    let sim = SimDevice::new(RotaryEncoder::default_addr(), RotaryEncoder::hardware_id());
+    let mut encoder = RotaryEncoder::new_with_default_addr(sim).init().unwrap();
+
+    encoder.driver().turn(5);
+    encoder.driver().turn(-2);
+    assert_eq!(encoder.position().unwrap(), 3);
+    assert_eq!(encoder.delta().unwrap(), 3);
+    assert_eq!(encoder.delta().unwrap(), 0);
+
+    encoder.set_position(100).unwrap();
+    assert_eq!(encoder.position().unwrap(), 100);
+
+    assert!(!encoder.button().unwrap());
+    encoder.driver().drive_pin(24, false);
+    assert!(encoder.button().unwrap());
+}
+
+#[test]
+fn neoslider_reads_adc() {
+    let sim = SimDevice::new(NeoSlider::default_addr(), NeoSlider::hardware_id());
+    let mut slider = NeoSlider::new_with_default_addr(sim).init().unwrap();
+
+    slider.driver().set_adc_channel(18, 512);
+    assert_eq!(slider.slider_value().unwrap(), 512);
+}
+
+#[test]
+fn arcade_leds_use_pwm() {
+    let sim = SimDevice::new(
+        ArcadeButton1x4::default_addr(),
+        ArcadeButton1x4::hardware_id(),
+    );
+    let mut arcade = ArcadeButton1x4::new_with_default_addr(sim).init().unwrap();
+
+    arcade.set_led_duty_cycles(&[10, 20, 30, 40]).unwrap();
+    let sim = arcade.driver();
+    assert_eq!(
+        [12, 13, 0, 1].map(|channel| sim.pwm_channel(channel)),
+        [10, 20, 30, 40]
+    );
+
+    arcade.driver().drive_pin(19, false);
+    assert_eq!(arcade.button_values().unwrap(), [false, true, false, false]);
+}
+
+#[test]
+fn status_module() {
+    let sim = SimDevice::new(0x49, HardwareId::SAMD09)
+        .with_product_id(4991)
+        .with_date_code(2021, 7, 4)
+        .with_temperature(25.5);
+    let mut dev = GenericDevice::new(0x49, sim).init().unwrap();
+
+    let info = dev.product_info().unwrap();
+    assert_eq!(
+        (info.id, info.year, info.month, info.day),
+        (4991, 2021, 7, 4)
+    );
+    assert_eq!(dev.temp().unwrap(), 25.5);
+    assert!(dev.capabilities().unwrap().encoder);
+    assert_eq!(dev.driver().resets(), 1);
+}
+
+#[test]
+fn wrong_address_is_nacked() {
+    let sim = SimDevice::new(0x36, HardwareId::SAMD09);
+    let mut dev = GenericDevice::new(0x37, sim);
+    let err = dev.hardware_id().unwrap_err();
+    assert!(matches!(
+        err,
+        SeesawError::I2c {
+            reg: [0x00, 0x01],
+            ..
+        }
+    ));
+    assert_eq!(err.module(), Some(Modules::Status));
+}
+
+#[test]
+fn pwm_channel_out_of_range_is_unsupported() {
+    let mut sim = SimDevice::new(0x3A, HardwareId::ATTINY817);
+    let channel = N_CHANNELS as u8;
+    assert!(matches!(
+        sim.register_write(0x3A, &[0x08, 0x01], &[channel, 0x12, 0x34]),
+        Err(SeesawError::I2c {
+            source: SimError::UnsupportedRegister([0x08, 0x01]),
+            ..
+        })
+    ));
+    assert!(sim
+        .register_write(0x3A, &[0x08, 0x02], &[channel, 0, 50])
+        .is_err());
+    assert!(sim
+        .register_write(0x3A, &[0x08, 0x01], &[channel - 1, 0x12, 0x34])
+        .is_ok());
+    assert_eq!(sim.pwm_channel(channel - 1), 0x1234);
+}
+
+#[test]
+#[should_panic(expected = "there is no GPIO pin 32")]
+fn driving_a_pin_past_the_gpio_registers_panics() {
+    let mut sim = SimDevice::new(0x30, HardwareId::ATTINY817);
+    sim.drive_pin(31, false);
+    sim.release_pin(31);
+    sim.drive_pin(32, false);
+}
+
+#[test]
+fn scan_identifies_devices() {
+    let sim = SimBus::new([
+        SimDevice::new(0x36, RotaryEncoder::hardware_id())
+            .with_product_id(RotaryEncoder::product_id())
+            .with_date_code(2021, 5, 6),
+        SimDevice::new(0x31, NeoKey1x4::hardware_id()).with_product_id(NeoKey1x4::product_id()),
+        SimDevice::new(0x50, HardwareId::ATTINY817).with_product_id(1234),
+        SimDevice::new(0x60, HardwareId::SAMD09).with_raw_hardware_id(0x42),
+    ]);
+    let seesaw = SeesawSingleThread::new(SimDelay::default(), sim);
+
+    let found = bus::scan::<8, _>(&mut seesaw.acquire_driver());
+    let found: Vec<_> = found
+        .iter()
+        .map(|dev| (dev.addr, dev.product_id, dev.device))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (0x31, 4980, Some(KnownDevice::NeoKey1x4)),
+            (0x36, 4991, Some(KnownDevice::RotaryEncoder)),
+            (0x50, 1234, None),
+        ]
+    );
+}
+
+#[test]
+fn scan_is_bounded_by_capacity() {
+    let sim = SimBus::new([
+        SimDevice::new(0x10, HardwareId::SAMD09),
+        SimDevice::new(0x11, HardwareId::SAMD09),
+        SimDevice::new(0x12, HardwareId::SAMD09),
+    ]);
+    let seesaw = SeesawSingleThread::new(SimDelay::default(), sim);
+
+    let found = bus::scan::<2, _>(&mut seesaw.acquire_driver());
+    assert_eq!(found.len(), 2);
+    assert_eq!(found.missed(), 1);
+    let first = found.iter().next().unwrap();
+    assert_eq!(first.addr, 0x10);
+    assert_eq!(first.hardware_id, HardwareId::SAMD09);
+    assert!(first.capabilities.gpio);
+}
+
+#[test]
+fn any_device_dispatches_by_kind() {
+    let sim = SimBus::new([
+        SimDevice::new(0x36, RotaryEncoder::hardware_id())
+            .with_product_id(RotaryEncoder::product_id()),
+        SimDevice::new(0x30, NeoKey1x4::hardware_id()).with_product_id(NeoKey1x4::product_id()),
+    ]);
+    let seesaw = SeesawSingleThread::new(SimDelay::default(), sim);
+
+    let mut devices: Vec<_> = bus::scan::<4, _>(&mut seesaw.acquire_driver())
+        .iter()
+        .filter_map(|found| AnyDevice::from_discovered(found, seesaw.acquire_driver()))
+        .map(|dev| dev.init().unwrap())
+        .collect();
+    assert_eq!(devices.len(), 2);
+
+    let neokeys = &mut devices[0];
+    assert_eq!(neokeys.kind(), KnownDevice::NeoKey1x4);
+    assert_eq!(neokeys.n_leds(), 4);
+    neokeys.set_nth_neopixel_color(1, 1, 2, 3).unwrap();
+    assert!(!neokeys.supports(Modules::Encoder));
+    assert!(matches!(
+        neokeys.position(),
+        Err(SeesawError::ModuleNotSupported(Modules::Encoder))
+    ));
+    assert!(matches!(
+        neokeys.analog_read(18),
+        Err(SeesawError::ModuleNotSupported(Modules::Adc))
+    ));
+
+    let encoder = &mut devices[1];
+    assert_eq!(encoder.kind(), KnownDevice::RotaryEncoder);
+    encoder.set_position(7).unwrap();
+    assert_eq!(encoder.position().unwrap(), 7);
+    assert!(matches!(
+        encoder.analog_write(4, 0xff),
+        Err(SeesawError::ModuleNotSupported(Modules::Timer))
+    ));
+}
+
+#[test]
+fn dynamic_device_follows_capabilities() {
+    let options = [
+        Modules::Status,
+        Modules::Gpio,
+        Modules::Adc,
+        Modules::Neopixel,
+    ]
+    .iter()
+    .fold(0, |opts, &module| opts | 1 << module.into_u8());
+    let sim = SimDevice::new(0x40, HardwareId::SAMD09)
+        .with_product_id(9999)
+        .with_options(options);
+    let mut dev = DynamicDevice::init(0x40, sim).unwrap();
+    assert_eq!(dev.hardware_id(), HardwareId::SAMD09);
+    assert_eq!(dev.product_info().id, 9999);
+    assert!(dev.supports(Modules::Adc) && !dev.supports(Modules::Encoder));
+
+    // SAMD09 pin 3 is ADC channel 1
+    dev.driver().set_adc_channel(1, 512);
+    assert_eq!(dev.analog_read(3).unwrap(), 512);
+
+    dev.enable_neopixel(6, 2).unwrap();
+    dev.set_neopixel_colors(&[(1, 2, 3), (4, 5, 6)])
+        .and_then(|_| dev.sync_neopixel())
+        .unwrap();
+    assert_eq!(dev.driver().neopixel_pin(), 6);
+    assert_eq!(dev.driver().shown_pixel(1), (4, 5, 6));
+
+    assert!(matches!(
+        dev.position(),
+        Err(SeesawError::ModuleNotSupported(Modules::Encoder))
+    ));
+    assert!(matches!(
+        dev.analog_write(4, 0x80),
+        Err(SeesawError::ModuleNotSupported(Modules::Timer))
+    ));
+}
+
+#[test]
+fn dynamic_device_rejects_unknown_hardware() {
+    let sim = SimDevice::new(0x40, HardwareId::SAMD09).with_raw_hardware_id(0x42);
+    assert!(matches!(
+        DynamicDevice::init(0x40, sim),
+        Err(SeesawError::InvalidHardwareId(0x42))
+    ));
+}
+
+#[test]
+fn recorded_trace_replays() {
+    let sim = SimDevice::new(RotaryEncoder::default_addr(), RotaryEncoder::hardware_id());
+    let driver = TracingDriver::new(sim, || 0, RingBuffer::<_, 64>::default());
+    let mut encoder = RotaryEncoder::new_with_default_addr(driver).init().unwrap();
+    encoder.driver().driver().turn(2);
+    assert_eq!(encoder.position().unwrap(), 2);
+    let trace = encoder.driver().sink().clone();
+    assert_eq!(trace.dropped(), 0);
+
+    let replay = Replay::new(trace.iter().cloned());
+    let mut encoder = RotaryEncoder::new_with_default_addr(replay).init().unwrap();
+    assert_eq!(encoder.position().unwrap(), 2);
+    encoder.driver().done();
+}
+
+#[test]
+fn timing_profile_paces_device() {
+    let timing = Timing {
+        read_delay_us: 10,
+        write_delay_us: 20,
+        reset_delay_us: 1_000,
+        adc_delay_us: 500,
+        neopixel_delay_us: 0,
+        touch_delay_us: 0,
+    };
+    let mut sim = SimDevice::new(NeoSlider::default_addr(), NeoSlider::hardware_id());
+    sim.set_adc_channel(18, 512);
+    let mut slider = NeoSlider::new_with_timing(NeoSlider::default_addr(), sim, timing);
+    slider.reset().unwrap();
+    assert_eq!(slider.driver().elapsed_us(), 20 + 1_000);
+    slider.hardware_id().unwrap();
+    assert_eq!(slider.driver().elapsed_us(), 1_030);
+    assert_eq!(slider.analog_read(18).unwrap(), 512);
+    assert_eq!(slider.driver().elapsed_us(), 1_530);
+}
+
+#[test]
+fn split_pins_share_the_bus() {
+    let sim = SimDevice::new(NeoKey1x4::default_addr(), NeoKey1x4::hardware_id());
+    let seesaw = SeesawSingleThread::new(SimDelay::default(), sim);
+    let mut neokeys = NeoKey1x4::new_with_default_addr(seesaw.acquire_driver());
+    let pins = neokeys.split();
+
+    let mut led = pins.p10.into_push_pull_output().unwrap();
+    led.set_high().unwrap();
+    assert_eq!(neokeys.digital_read_bulk().unwrap() >> 10 & 1, 1);
+    led.toggle().unwrap();
+    assert_eq!(neokeys.digital_read_bulk().unwrap() >> 10 & 1, 0);
+
+    let pulled_up = pins.p11.into_pull_up_input().unwrap();
+    assert!(pulled_up.is_high().unwrap());
+    let pulled_down = pins.p14.into_pull_down_input().unwrap();
+    assert!(pulled_down.is_low().unwrap());
+}
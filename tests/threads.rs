@@ -0,0 +1,64 @@
+use adafruit_seesaw::{
+    bus::Bus,
+    prelude::*,
+    sim::{SimBus, SimDelay, SimDevice},
+    Seesaw, SeesawCriticalSection, SeesawStd,
+};
+use shared_bus::BusMutex;
+
+fn sim_bus() -> SimBus<2> {
+    SimBus::new([
+        SimDevice::new(NeoKey1x4::default_addr(), NeoKey1x4::hardware_id()),
+        SimDevice::new(RotaryEncoder::default_addr(), RotaryEncoder::hardware_id()),
+    ])
+}
+
+/// Drive a NeoKey1x4 and a RotaryEncoder on the same bus from two threads
+fn devices_on_separate_threads<M>(seesaw: &Seesaw<M>)
+where
+    M: BusMutex<Bus = Bus<SimDelay, SimBus<2>>> + Sync,
+{
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            let mut neokeys = NeoKey1x4::new_with_default_addr(seesaw.acquire_driver())
+                .init()
+                .expect("Failed to start NeoKey1x4");
+            for i in 0..50 {
+                neokeys
+                    .set_nth_neopixel_color(i % 4, i as u8, 0, 0)
+                    .unwrap();
+                neokeys.sync_neopixel().unwrap();
+                assert_eq!(neokeys.keys().unwrap(), 0b1111);
+            }
+        });
+        s.spawn(|| {
+            let mut encoder = RotaryEncoder::new_with_default_addr(seesaw.acquire_driver())
+                .init()
+                .expect("Failed to start RotaryEncoder");
+            for i in 0..50 {
+                encoder.set_position(i).unwrap();
+                assert_eq!(encoder.position().unwrap(), i);
+            }
+        });
+    });
+}
+
+/// `SeesawCortexM` only works on a Cortex-M, so this just checks that a device
+/// can still be built on it
+#[cfg(feature = "cortex-m")]
+const _: fn(SimDelay, SimBus<2>) = |delay, bus| {
+    let seesaw: adafruit_seesaw::SeesawCortexM<_> = Seesaw::new(delay, bus);
+    let _ = NeoKey1x4::new_with_default_addr(seesaw.acquire_driver()).init();
+};
+
+#[test]
+fn std_mutex_shares_bus_between_threads() {
+    let seesaw: SeesawStd<_> = Seesaw::new(SimDelay::default(), sim_bus());
+    devices_on_separate_threads(&seesaw);
+}
+
+#[test]
+fn critical_section_mutex_shares_bus_between_threads() {
+    let seesaw: SeesawCriticalSection<_> = Seesaw::new(SimDelay::default(), sim_bus());
+    devices_on_separate_threads(&seesaw);
+}